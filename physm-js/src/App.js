@@ -29,6 +29,8 @@ const ropeSegmentCount = 5;
 const cartMass = 250;
 const maxCartForce = 8500;
 const cartResistance = 5;
+const dragStiffness = 500;
+const maxDragForce = 8500;
 
 const initialScale = 12;
 const MIN_ANIMATION_FPS = 5;
@@ -176,20 +178,24 @@ function useMouse(elementRef = required('elementRef')) {
   const [endLocation, setEndLocation] = useState(null);
 
   useEffect(() => {
+    const element = elementRef.current;
+    function getLocation(event) {
+      const rect = element.getBoundingClientRect();
+      return [event.clientX - rect.left, event.clientY - rect.top];
+    }
     function handleMouseDown(event) {
       console.log('start', event);
-      setStartLocation([event.x, event.y]);
+      setStartLocation(getLocation(event));
       setEndLocation(null);
     }
     function handleMouseMove(event) {
-      setEndLocation([event.x, event.y]);
+      setEndLocation(getLocation(event));
     }
     function handleMouseUp(event) {
       console.log('end');
       setStartLocation(null);
       setEndLocation(null);
     }
-    const element = elementRef.current;
     element.addEventListener('mousedown', handleMouseDown);
     element.addEventListener('mousemove', handleMouseMove);
     element.addEventListener('mouseup', handleMouseUp);
@@ -204,7 +210,7 @@ function useMouse(elementRef = required('elementRef')) {
     startLocation && endLocation
       ? [endLocation[0] - startLocation[0], endLocation[1] - startLocation[1]]
       : null;
-  return locationDelta;
+  return { startLocation, locationDelta };
 }
 
 function useTouch(elementRef = required('elementRef')) {
@@ -298,7 +304,6 @@ function handleViewControls({
 
 function getExternalForceMap(
   pressedKeys = required('pressedKeys'),
-  clickLocationDelta = required('clickLocationDelta'),
   touchLocationDelta = required('touchLocationDelta'),
   deltaTime = required('deltaTime'),
 ) {
@@ -308,9 +313,6 @@ function getExternalForceMap(
   } else if (pressedKeys.has('KeyD') || pressedKeys.has('ArrowRight')) {
     cartForce += maxCartForce;
   }
-  if (clickLocationDelta) {
-    cartForce += (clickLocationDelta[0] / 200) * maxCartForce;
-  }
   if (touchLocationDelta) {
     cartForce += (touchLocationDelta[0] / 60) * maxCartForce;
   }
@@ -320,6 +322,59 @@ function getExternalForceMap(
   return new Map([[cart.id, cartForce]]);
 }
 
+function getWorldLocation(location, translation, scale) {
+  // (inverse of the view transformation in getViewXformMatrix)
+  return [
+    (location[0] - 300) / scale - translation[0],
+    -(location[1] - 300) / scale - translation[1],
+  ];
+}
+
+function getGrab(solver = required('solver'), location = required('location')) {
+  /**
+   * Find the weight nearest to a world-space location, and return its frame
+   * along with the grabbed point in the frame's coordinate system.
+   */
+  let grab = null;
+  let minDistance = Infinity;
+  for (let frame of scene.sortedFrames) {
+    const localLocation = solver.getLocalPosition(frame.id, location);
+    for (let weight of frame.weights) {
+      const [x, y] = weight.position.dataSync();
+      const distance = Math.hypot(localLocation[0] - x, localLocation[1] - y);
+      if (distance < minDistance) {
+        minDistance = distance;
+        grab = { frameId: frame.id, position: localLocation };
+      }
+    }
+  }
+  return grab;
+}
+
+function addDragForces(
+  externalForceMap = required('externalForceMap'),
+  solver = required('solver'),
+  grab = required('grab'),
+  locationDelta = required('locationDelta'),
+  scale = required('scale'),
+) {
+  let force = [
+    (locationDelta[0] / scale) * dragStiffness,
+    (-locationDelta[1] / scale) * dragStiffness,
+  ];
+  const magnitude = Math.hypot(...force);
+  if (magnitude > maxDragForce) {
+    force = force.map((value) => (value / magnitude) * maxDragForce);
+  }
+  const pointForces = solver.getPointForces(grab.frameId, grab.position, force);
+  for (let [frameId, pointForce] of pointForces) {
+    externalForceMap.set(
+      frameId,
+      (externalForceMap.get(frameId) || 0) + pointForce,
+    );
+  }
+}
+
 function simulate(
   solver = required('solver'),
   externalForceMap = required('externalForceMap'),
@@ -396,13 +451,14 @@ function App({ rsWasmModule }) {
   const [scale, setScale] = useState(initialScale);
   const svgRef = React.useRef();
   const pressedKeys = useKeyboard();
-  const clickLocationDelta = useMouse(svgRef);
+  const mouse = useMouse(svgRef);
   const touchLocationDelta = useTouch(svgRef);
   const [stateMap, setStateMap] = useState(scene.getInitialStateMap());
   const viewXformMatrix = getViewXformMatrix(translation, scale);
   const sceneDomElement = scene.getDomElement(stateMap, viewXformMatrix);
   viewXformMatrix.dispose();
   const solver = useRef(null);
+  const grab = useRef(null);
 
   useEffect(() => {
     solver.current = createSolver(scene, rsWasmModule);
@@ -420,10 +476,26 @@ function App({ rsWasmModule }) {
     if (!paused) {
       const externalForceMap = getExternalForceMap(
         pressedKeys,
-        clickLocationDelta,
         touchLocationDelta,
         deltaTime,
       );
+      if (!mouse.startLocation) {
+        grab.current = null;
+      } else if (!grab.current) {
+        grab.current = getGrab(
+          solver.current,
+          getWorldLocation(mouse.startLocation, translation, scale),
+        );
+      }
+      if (grab.current && mouse.locationDelta) {
+        addDragForces(
+          externalForceMap,
+          solver.current,
+          grab.current,
+          mouse.locationDelta,
+          scale,
+        );
+      }
       const newStateMap = simulate(solver.current, externalForceMap, deltaTime);
       setStateMap(newStateMap);
     }
@@ -17,6 +17,7 @@ export default class Frame {
     initialState = ZERO_STATE,
     id = null,
     typeName = null,
+    coordinateCount = 1,
   } = {}) {
     this.id = id != null ? id : generateRandomId();
     this.typeName = typeName || this.constructor.name;
@@ -26,6 +27,9 @@ export default class Frame {
     this.frames = frames;
     this.resistance = resistance;
     this.initialState = coerceStateTuple(initialState);
+    // Frames such as FreeFrame (3) and FixedFrame (0) don't have exactly one
+    // coordinate, which RsSolver needs to know to lay out its buffers.
+    this.coordinateCount = coordinateCount;
   }
 
   dispose() {
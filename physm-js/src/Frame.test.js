@@ -16,6 +16,7 @@ describe('Frame class', () => {
     expect(frame.frames).toEqual([]);
     expect(frame.resistance).toEqual(0);
     expect(frame.initialState).toEqual([0, 0]);
+    expect(frame.coordinateCount).toEqual(1);
   });
 
   test('.dispose method', () => {
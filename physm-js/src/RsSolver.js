@@ -9,14 +9,29 @@ export default class RsSolver extends Solver {
     { rungeKutta = true } = {},
   ) {
     super(scene);
-    this.stateBuffer = new Float64Array(this.scene.sortedFrames.length * 2);
-    this.extForceBuffer = new Float64Array(this.scene.sortedFrames.length);
-    this.resetStateMap();
     console.log('[js] Creating solver context');
     const sceneJson = JSON.stringify(scene.toJsonObj());
     this.context = new rsWasmModule.SolverContext(sceneJson);
     this.context.setRungeKutta(rungeKutta);
     console.log('[js] Created solver context:', this.context);
+    // The Rust solver flattens its states and forces per coordinate rather
+    // than per frame, so keep track of where each frame's coordinates start.
+    let coordinateCount = 0;
+    this.coordinateOffsets = new Map(
+      this.scene.sortedFrames.map((frame) => {
+        const offset = coordinateCount;
+        coordinateCount += frame.coordinateCount;
+        return [frame.id, offset];
+      }),
+    );
+    if (coordinateCount !== this.context.coordinateCount) {
+      throw new Error(
+        `Expected ${this.context.coordinateCount} coordinates; got ${coordinateCount}`,
+      );
+    }
+    this.stateBuffer = new Float64Array(coordinateCount * 2);
+    this.extForceBuffer = new Float64Array(coordinateCount);
+    this.resetStateMap();
   }
 
   dispose() {
@@ -26,33 +41,78 @@ export default class RsSolver extends Solver {
     this.context = null;
   }
 
+  getSingleCoordinateFrames() {
+    // Only frames with a single coordinate have a `[q, qd]` state and a
+    // scalar external force in the maps passed to and from the solver.
+    return this.scene.sortedFrames.filter(
+      (frame) => frame.coordinateCount === 1,
+    );
+  }
+
   getStateMap() {
     return new Map(
-      this.scene.sortedFrames.map((frame, index) => [
-        frame.id,
-        [this.stateBuffer[index * 2], this.stateBuffer[index * 2 + 1]],
-      ]),
+      this.getSingleCoordinateFrames().map((frame) => {
+        const offset = this.coordinateOffsets.get(frame.id);
+        return [
+          frame.id,
+          [this.stateBuffer[offset * 2], this.stateBuffer[offset * 2 + 1]],
+        ];
+      }),
     );
   }
 
   setStateMap(stateMap = required('stateMap')) {
-    this.stateBuffer.set(
-      this.scene.sortedFrames.flatMap(
-        (frame) => stateMap.get(frame.id) || [0, 0],
+    this.stateBuffer.fill(0);
+    this.getSingleCoordinateFrames().forEach((frame) => {
+      const offset = this.coordinateOffsets.get(frame.id);
+      this.stateBuffer.set(stateMap.get(frame.id) || [0, 0], offset * 2);
+    });
+  }
+
+  getLocalPosition(
+    frameId = required('frameId'),
+    position = required('position'),
+  ) {
+    return Array.from(
+      this.context.getLocalPosition(
+        this.stateBuffer,
+        frameId,
+        Float64Array.from(position),
       ),
     );
   }
 
+  getPointForces(
+    frameId = required('frameId'),
+    position = required('position'),
+    force = required('force'),
+  ) {
+    const forces = this.context.getPointForces(
+      this.stateBuffer,
+      frameId,
+      Float64Array.from(position),
+      Float64Array.from(force),
+    );
+    return new Map(
+      this.getSingleCoordinateFrames().map((frame) => [
+        frame.id,
+        forces[this.coordinateOffsets.get(frame.id)],
+      ]),
+    );
+  }
+
   tick(
     deltaTime = required('deltaTime'),
     tickCount = 1,
     externalForceMap = null,
   ) {
-    this.extForceBuffer.set(
-      this.scene.sortedFrames.map((frame) =>
-        externalForceMap ? externalForceMap.get(frame.id) || 0 : 0,
-      ),
-    );
+    this.extForceBuffer.fill(0);
+    if (externalForceMap) {
+      this.getSingleCoordinateFrames().forEach((frame) => {
+        this.extForceBuffer[this.coordinateOffsets.get(frame.id)] =
+          externalForceMap.get(frame.id) || 0;
+      });
+    }
     this.context.tick(this.stateBuffer, deltaTime, tickCount, this.extForceBuffer);
     for (let i = 0; i < this.stateBuffer.length; i++) {
       if (isNaN(this.stateBuffer[i])) {
@@ -0,0 +1,67 @@
+import Frame from './Frame';
+import RotationalFrame from './RotationalFrame';
+import RsSolver from './RsSolver';
+import Scene from './Scene';
+import Weight from './Weight';
+
+describe('RsSolver class', () => {
+  // A pendulum hanging off a free-floating puck, so that the puck's three
+  // coordinates come ahead of the pendulum's in the flattened buffers.
+  const scene = new Scene({
+    frames: [
+      new Frame({
+        id: 'puck',
+        typeName: 'FreeFrame',
+        coordinateCount: 3,
+        weights: [new Weight(4)],
+        frames: [
+          new RotationalFrame({
+            id: 'pendulum',
+            initialState: [0.3, -1.2],
+            weights: [new Weight(5, { position: [10, 0] })],
+          }),
+        ],
+      }),
+    ],
+  });
+
+  // Stands in for physm-rs, returning one force per coordinate the way
+  // `SolverContext.getPointForces` does.
+  class FakeSolverContext {
+    constructor(sceneJson) {
+      this.sceneJson = sceneJson;
+    }
+
+    get coordinateCount() {
+      return 4;
+    }
+
+    setRungeKutta(rungeKutta) {}
+
+    getPointForces(stateBuffer, frameId, position, force) {
+      expect(stateBuffer.length).toEqual(8);
+      return Float64Array.from([1, 2, 3, 4]);
+    }
+  }
+  const fakeRsWasmModule = { SolverContext: FakeSolverContext };
+
+  test('constructor lays out states by coordinate', () => {
+    const solver = new RsSolver(scene, fakeRsWasmModule);
+    expect(solver.coordinateOffsets).toEqual(
+      new Map([
+        ['puck', 0],
+        ['pendulum', 3],
+      ]),
+    );
+    expect(solver.stateBuffer).toEqual(
+      Float64Array.from([0, 0, 0, 0, 0, 0, 0.3, -1.2]),
+    );
+    expect(solver.getStateMap()).toEqual(new Map([['pendulum', [0.3, -1.2]]]));
+  });
+
+  test('.getPointForces method', () => {
+    const solver = new RsSolver(scene, fakeRsWasmModule);
+    const pointForces = solver.getPointForces('pendulum', [10, 0], [0, -1]);
+    expect(pointForces).toEqual(new Map([['pendulum', 4]]));
+  });
+});
@@ -37,6 +37,21 @@ export default class Solver {
     this.setStateMap(this.scene.getInitialStateMap());
   }
 
+  getLocalPosition(
+    frameId = required('frameId'),
+    position = required('position'),
+  ) {
+    throw new NotImplementedError('abstract method');
+  }
+
+  getPointForces(
+    frameId = required('frameId'),
+    position = required('position'),
+    force = required('force'),
+  ) {
+    throw new NotImplementedError('abstract method');
+  }
+
   tick(
     deltaTime = required('deltaTime'),
     tickCount = 1,
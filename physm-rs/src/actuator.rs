@@ -0,0 +1,154 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+
+#[derive(Debug, PartialEq)]
+pub struct Actuator {
+    pub frame_id: FrameId,
+    pub gear_ratio: f64,
+    pub no_load_speed: f64,
+    pub rotor_inertia: f64,
+    pub stall_torque: f64,
+}
+
+impl Actuator {
+    pub fn new(frame_id: FrameId, stall_torque: f64, no_load_speed: f64) -> Self {
+        Self {
+            frame_id,
+            gear_ratio: 1.,
+            no_load_speed,
+            rotor_inertia: 0.,
+            stall_torque,
+        }
+    }
+
+    pub fn set_gear_ratio(mut self, gear_ratio: f64) -> Self {
+        self.gear_ratio = gear_ratio;
+        self
+    }
+
+    pub fn set_rotor_inertia(mut self, rotor_inertia: f64) -> Self {
+        self.rotor_inertia = rotor_inertia;
+        self
+    }
+
+    /// Returns the generalized force applied to the frame for a commanded effort in the range
+    /// `[-1, 1]` (i.e. the fraction of rated voltage), following a linear DC motor torque-speed
+    /// curve.  The motor torque is limited to the stall torque, as a current-limited driver would.
+    pub fn get_force(&self, command: f64, qd: f64) -> f64 {
+        let effort = command.clamp(-1., 1.);
+        let motor_speed = self.gear_ratio * qd;
+        let motor_torque = self.stall_torque * (effort - motor_speed / self.no_load_speed);
+        self.gear_ratio * motor_torque.max(-self.stall_torque).min(self.stall_torque)
+    }
+
+    /// Returns the rotor inertia as seen from the frame's side of the gearbox.
+    pub fn get_reflected_inertia(&self) -> f64 {
+        self.gear_ratio * self.gear_ratio * self.rotor_inertia
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let no_load_speed = json::map_value_item(value, "noLoadSpeed", json::value_to_f64)?;
+        if no_load_speed <= 0. {
+            return Err(Error(format!(
+                "Expected positive `noLoadSpeed`; got {}",
+                no_load_speed
+            )));
+        }
+        Ok(Actuator {
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            gear_ratio: obj
+                .get("gearRatio")
+                .map(json::value_to_f64)
+                .transpose()?
+                .unwrap_or(1.),
+            no_load_speed,
+            rotor_inertia: json::map_obj_item_or_default(obj, "rotorInertia", json::value_to_f64)?,
+            stall_torque: json::map_value_item(value, "stallTorque", json::value_to_f64)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let actuator = Actuator::new("a".into(), 3., 20.);
+        assert_eq!(actuator.frame_id, "a");
+        assert_eq!(actuator.stall_torque, 3.);
+        assert_eq!(actuator.no_load_speed, 20.);
+        assert_eq!(actuator.gear_ratio, 1.);
+        assert_eq!(actuator.rotor_inertia, 0.);
+
+        let actuator = actuator.set_gear_ratio(5.).set_rotor_inertia(0.1);
+        assert_eq!(actuator.gear_ratio, 5.);
+        assert_eq!(actuator.rotor_inertia, 0.1);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "frame": "a",
+              "gearRatio": 4,
+              "noLoadSpeed": 30,
+              "rotorInertia": 0.5,
+              "stallTorque": 2
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Actuator::from_json_value(&json_value).unwrap(),
+            Actuator::new("a".into(), 2., 30.)
+                .set_gear_ratio(4.)
+                .set_rotor_inertia(0.5)
+        );
+
+        let json = r#"{"frame": "a", "noLoadSpeed": 30, "stallTorque": 2}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Actuator::from_json_value(&json_value).unwrap(),
+            Actuator::new("a".into(), 2., 30.)
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_bad_speed() {
+        let json = r#"{"frame": "a", "noLoadSpeed": 0, "stallTorque": 2}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Actuator::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Expected positive `noLoadSpeed`; got 0"
+        );
+    }
+
+    #[test]
+    fn test_get_force() {
+        let actuator = Actuator::new("a".into(), 2., 10.).set_gear_ratio(4.);
+        // Stalled at full effort:
+        assert_abs_diff_eq!(actuator.get_force(1., 0.), 8.);
+        // Commands beyond full effort saturate:
+        assert_abs_diff_eq!(actuator.get_force(5., 0.), 8.);
+        assert_abs_diff_eq!(actuator.get_force(-5., 0.), -8.);
+        // No torque at no-load speed (measured on the motor side of the gearbox):
+        assert_abs_diff_eq!(actuator.get_force(1., 2.5), 0.);
+        // Half speed yields half torque:
+        assert_abs_diff_eq!(actuator.get_force(1., 1.25), 4.);
+        // Back-driving is limited to the stall torque:
+        assert_abs_diff_eq!(actuator.get_force(1., -10.), 8.);
+        // Zero effort acts as a brake:
+        assert_abs_diff_eq!(actuator.get_force(0., 1.25), -4.);
+    }
+
+    #[test]
+    fn test_get_reflected_inertia() {
+        let actuator = Actuator::new("a".into(), 2., 10.)
+            .set_gear_ratio(4.)
+            .set_rotor_inertia(0.5);
+        assert_abs_diff_eq!(actuator.get_reflected_inertia(), 8.);
+    }
+}
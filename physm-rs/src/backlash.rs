@@ -0,0 +1,95 @@
+use crate::json;
+use crate::Error;
+
+/// Play in a joint or coupling, e.g. gear backlash or a loose pin, which leaves the coordinate
+/// free within a gap of the given `width` centered on its driven or rest position.  Past either
+/// end of the gap, the joint engages as a stiff spring, and `damping` (which only acts while
+/// engaged) sets how much of the impact is absorbed instead of bouncing back.
+#[derive(Debug, PartialEq)]
+pub struct Backlash {
+    pub damping: f64,
+    pub stiffness: f64,
+    pub width: f64,
+}
+
+impl Backlash {
+    pub fn new(width: f64, stiffness: f64) -> Self {
+        Self {
+            damping: 0.,
+            stiffness,
+            width,
+        }
+    }
+
+    pub fn set_damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Returns the generalized force pushing the coordinate back into the gap, given its error
+    /// from the center of the gap and the error's rate of change.  The damping never pulls the
+    /// coordinate out of the gap, so engaged joints don't stick as they separate.
+    pub fn get_force(&self, error: f64, error_rate: f64) -> f64 {
+        let half_width = self.width / 2.;
+        if error > half_width {
+            (-self.stiffness * (error - half_width) - self.damping * error_rate).min(0.)
+        } else if error < -half_width {
+            (-self.stiffness * (error + half_width) - self.damping * error_rate).max(0.)
+        } else {
+            0.
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let backlash = Backlash {
+            damping: json::map_obj_item_or_default(obj, "damping", json::value_to_f64)?,
+            stiffness: json::map_value_item(value, "stiffness", json::value_to_f64)?,
+            width: json::map_value_item(value, "width", json::value_to_f64)?,
+        };
+        if backlash.width < 0. || backlash.stiffness <= 0. {
+            return Err(Error(format!(
+                "Expected backlash with a non-negative width and positive stiffness; got {}",
+                value
+            )));
+        }
+        Ok(backlash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_force() {
+        let backlash = Backlash::new(0.2, 100.).set_damping(10.);
+        assert_eq!(backlash.get_force(0.05, 1.), 0.);
+        assert_eq!(backlash.get_force(-0.1, -1.), 0.);
+        assert_abs_diff_eq!(backlash.get_force(0.2, 0.), -10., epsilon = 1e-12);
+        assert_abs_diff_eq!(backlash.get_force(0.2, 0.5), -15., epsilon = 1e-12);
+        assert_abs_diff_eq!(backlash.get_force(-0.2, -0.5), 15., epsilon = 1e-12);
+        // Separating quickly doesn't pull the coordinate back out:
+        assert_eq!(backlash.get_force(0.2, -2.), 0.);
+        assert_eq!(backlash.get_force(-0.2, 2.), 0.);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"{"damping": 2, "stiffness": 1000, "width": 0.1}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Backlash::from_json_value(&json_value).unwrap(),
+            Backlash::new(0.1, 1000.).set_damping(2.)
+        );
+
+        let json = r#"{"stiffness": 0, "width": 0.1}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Backlash::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected backlash with a non-negative width and positive stiffness; got {"stiffness":0,"width":0.1}"#
+        );
+    }
+}
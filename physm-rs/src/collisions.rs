@@ -0,0 +1,59 @@
+use crate::json;
+use crate::Error;
+
+/// Collisions between weights on different frames (e.g. of two pendulums swinging into each
+/// other, or the balls of a Newton's cradle), which treat each weight with a nonzero `radius` as a
+/// disc.  Weights on the same frame never move relative to each other, so they don't collide, and
+/// neither do weights on a frame and its parent whose discs both cover the joint between them
+/// (e.g. the ends of two links of a chain).  Other weights on a frame and its parent collide.
+///
+/// As with obstacles, `restitution` sets how much of the approaching speed a collision gives
+/// back, and Coulomb `friction` resists the weights' surfaces sliding against each other.
+#[derive(Debug, PartialEq)]
+pub struct Collisions {
+    pub friction: f64,
+    pub restitution: f64,
+}
+
+impl Collisions {
+    pub fn new(restitution: f64) -> Self {
+        Self {
+            friction: 0.,
+            restitution,
+        }
+    }
+
+    pub fn set_friction(mut self, friction: f64) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Collisions {
+            friction: json::map_obj_item_or_default(obj, "friction", json::value_to_f64)?,
+            restitution: json::map_obj_item_or_default(obj, "restitution", json::value_to_f64)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"{"friction": 0.2, "restitution": 0.9}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Collisions::from_json_value(&json_value).unwrap(),
+            Collisions::new(0.9).set_friction(0.2)
+        );
+
+        let json_value: serde_json::Value = serde_json::from_str("{}").unwrap();
+        assert_eq!(
+            Collisions::from_json_value(&json_value).unwrap(),
+            Collisions::new(0.)
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use crate::json;
+use crate::Backlash;
+use crate::Error;
+use crate::FrameId;
+
+/// Linear constraint between two frames' coordinates, `q = ratio * q_driver + offset`, e.g. for
+/// gear trains, belts and rack-and-pinion drives.  Couplings are perfectly tight unless given
+/// some `backlash`, which lets the error wander within the gap before the coupling engages.
+#[derive(Debug, PartialEq)]
+pub struct Coupling {
+    pub backlash: Option<Backlash>,
+    pub driver_frame_id: FrameId,
+    pub frame_id: FrameId,
+    pub offset: f64,
+    pub ratio: f64,
+}
+
+impl Coupling {
+    pub fn new(frame_id: FrameId, driver_frame_id: FrameId) -> Self {
+        Self {
+            backlash: None,
+            driver_frame_id,
+            frame_id,
+            offset: 0.,
+            ratio: 1.,
+        }
+    }
+
+    pub fn set_backlash(mut self, backlash: Backlash) -> Self {
+        self.backlash = Some(backlash);
+        self
+    }
+
+    pub fn set_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn set_ratio(mut self, ratio: f64) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    pub fn get_error(&self, q: f64, driver_q: f64) -> f64 {
+        q - self.ratio * driver_q - self.offset
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let coupling = Coupling {
+            backlash: json::map_obj_item_or_default(obj, "backlash", |value| {
+                Backlash::from_json_value(value).map(Some)
+            })?,
+            driver_frame_id: json::map_value_item(value, "driverFrame", json::value_to_str)?.into(),
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            offset: json::map_obj_item_or_default(obj, "offset", json::value_to_f64)?,
+            ratio: obj
+                .get("ratio")
+                .map(json::value_to_f64)
+                .transpose()?
+                .unwrap_or(1.),
+        };
+        if coupling.frame_id == coupling.driver_frame_id {
+            return Err(Error(format!(
+                "Expected coupling between two different frames; got {}",
+                value
+            )));
+        }
+        Ok(coupling)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let coupling = Coupling::new("a".into(), "b".into());
+        assert_eq!(coupling.frame_id, "a");
+        assert_eq!(coupling.driver_frame_id, "b");
+        assert_eq!(coupling.ratio, 1.);
+        assert_eq!(coupling.offset, 0.);
+        assert_eq!(coupling.backlash, None);
+
+        let coupling = coupling.set_ratio(-2.).set_offset(0.5);
+        assert_eq!(coupling.ratio, -2.);
+        assert_eq!(coupling.offset, 0.5);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "backlash": {"stiffness": 1000, "width": 0.1},
+              "driverFrame": "b",
+              "frame": "a",
+              "offset": 0.5,
+              "ratio": -2
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Coupling::from_json_value(&json_value).unwrap(),
+            Coupling::new("a".into(), "b".into())
+                .set_ratio(-2.)
+                .set_offset(0.5)
+                .set_backlash(Backlash::new(0.1, 1000.))
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_same_frame() {
+        let json = r#"{"driverFrame": "a", "frame": "a"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Coupling::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected coupling between two different frames; got {"driverFrame":"a","frame":"a"}"#
+        );
+    }
+
+    #[test]
+    fn test_get_error() {
+        let coupling = Coupling::new("a".into(), "b".into())
+            .set_ratio(-2.)
+            .set_offset(0.5);
+        assert_abs_diff_eq!(coupling.get_error(-1.5, 1.), 0.);
+        assert_abs_diff_eq!(coupling.get_error(1., 1.), 2.5);
+    }
+}
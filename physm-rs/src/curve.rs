@@ -0,0 +1,309 @@
+use crate::json;
+use crate::Error;
+use crate::Position;
+
+/// Planar path followed by a `CurveFrame`, parameterized by a single coordinate.
+#[derive(Debug, PartialEq)]
+pub enum Curve {
+    /// Circle parameterized by arc length, starting at `start_angle` and running
+    /// counter-clockwise.
+    Arc {
+        center: Position,
+        radius: f64,
+        start_angle: f64,
+    },
+    /// Axis-aligned ellipse parameterized by its eccentric anomaly (i.e. the angle of the
+    /// corresponding point on a unit circle), starting from the positive x-axis.
+    Ellipse { center: Position, radii: [f64; 2] },
+    /// Natural cubic spline through control points, which the parameter passes through at
+    /// `0, 1, 2, ...`.  The path continues in a straight line beyond the end points.
+    Spline {
+        points: Vec<Position>,
+        second_derivatives: Vec<[f64; 2]>,
+    },
+}
+
+fn get_natural_spline_second_derivatives(values: &[f64]) -> Vec<f64> {
+    // Solve the tridiagonal system `m[i-1] + 4 * m[i] + m[i+1] = 6 * (y[i-1] - 2 * y[i] + y[i+1])`
+    // with the natural end conditions `m[0] = m[n-1] = 0` (Thomas algorithm):
+    let count = values.len();
+    let mut second_derivatives = vec![0.; count];
+    if count < 3 {
+        return second_derivatives;
+    }
+    let mut diagonals = vec![4.; count];
+    let mut rhs = (0..count)
+        .map(|index| match index == 0 || index == count - 1 {
+            true => 0.,
+            false => 6. * (values[index - 1] - 2. * values[index] + values[index + 1]),
+        })
+        .collect::<Vec<f64>>();
+    for index in 2..count - 1 {
+        let factor = 1. / diagonals[index - 1];
+        diagonals[index] -= factor;
+        rhs[index] -= factor * rhs[index - 1];
+    }
+    for index in (1..count - 1).rev() {
+        second_derivatives[index] = (rhs[index] - second_derivatives[index + 1]) / diagonals[index];
+    }
+    second_derivatives
+}
+
+impl Curve {
+    pub fn new_spline(points: Vec<Position>) -> Self {
+        let get_axis_second_derivatives = |axis: usize| {
+            let values = points.iter().map(|point| point.0[axis]).collect::<Vec<_>>();
+            get_natural_spline_second_derivatives(&values)
+        };
+        let second_derivatives = get_axis_second_derivatives(0)
+            .into_iter()
+            .zip(get_axis_second_derivatives(1))
+            .map(|(x, y)| [x, y])
+            .collect();
+        Curve::Spline {
+            points,
+            second_derivatives,
+        }
+    }
+
+    /// Returns the point on the curve at parameter `q`, along with its first and second
+    /// derivatives with respect to `q`.
+    pub fn get_derivatives(&self, q: f64) -> [[f64; 2]; 3] {
+        match self {
+            Curve::Arc {
+                center,
+                radius,
+                start_angle,
+            } => {
+                let angle = start_angle + q / radius;
+                let (sin, cos) = angle.sin_cos();
+                [
+                    [center.0[0] + radius * cos, center.0[1] + radius * sin],
+                    [-sin, cos],
+                    [-cos / radius, -sin / radius],
+                ]
+            }
+            Curve::Ellipse { center, radii } => {
+                let (sin, cos) = q.sin_cos();
+                [
+                    [center.0[0] + radii[0] * cos, center.0[1] + radii[1] * sin],
+                    [-radii[0] * sin, radii[1] * cos],
+                    [-radii[0] * cos, -radii[1] * sin],
+                ]
+            }
+            Curve::Spline {
+                points,
+                second_derivatives,
+            } => {
+                let last_index = points.len() - 1;
+                if last_index == 0 {
+                    return [points[0].0, [0., 0.], [0., 0.]];
+                }
+                let segment_index = (q.floor().max(0.) as usize).min(last_index - 1);
+                let u = (q - segment_index as f64).clamp(0., 1.);
+                let extension = q - segment_index as f64 - u;
+                let (y1, y2) = (points[segment_index].0, points[segment_index + 1].0);
+                let (m1, m2) = (
+                    second_derivatives[segment_index],
+                    second_derivatives[segment_index + 1],
+                );
+                let mut derivatives = [[0.; 2]; 3];
+                for axis in 0..2 {
+                    let v = 1. - u;
+                    let point = v * y1[axis]
+                        + u * y2[axis]
+                        + ((v * v * v - v) * m1[axis] + (u * u * u - u) * m2[axis]) / 6.;
+                    let tangent = y2[axis] - y1[axis]
+                        + ((1. - 3. * v * v) * m1[axis] + (3. * u * u - 1.) * m2[axis]) / 6.;
+                    derivatives[0][axis] = point + extension * tangent;
+                    derivatives[1][axis] = tangent;
+                    derivatives[2][axis] = match extension == 0. {
+                        true => v * m1[axis] + u * m2[axis],
+                        false => 0.,
+                    };
+                }
+                derivatives
+            }
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "Arc" => Curve::Arc {
+                center: json::map_obj_item_or_default(obj, "center", Position::from_json_value)?,
+                radius: json::map_value_item(value, "radius", json::value_to_f64)?,
+                start_angle: json::map_obj_item_or_default(obj, "startAngle", json::value_to_f64)?,
+            },
+            "Ellipse" => Curve::Ellipse {
+                center: json::map_obj_item_or_default(obj, "center", Position::from_json_value)?,
+                radii: json::map_value_item(value, "radii", Position::from_json_value)?.0,
+            },
+            "Spline" => {
+                let points = json::map_value_item(value, "points", |value| {
+                    json::value_to_vec(value, Position::from_json_value)
+                })?;
+                if points.len() < 2 {
+                    return Err(Error(format!(
+                        "Expected at least two spline points; got {}",
+                        value
+                    )));
+                }
+                Curve::new_spline(points)
+            }
+            _ => return Err(Error(format!("Invalid curve type: {}", type_name))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    fn assert_derivatives_consistent(curve: &Curve, q: f64) {
+        let delta = 1e-6;
+        let [_, tangent, second_derivative] = curve.get_derivatives(q);
+        let [point1, tangent1, _] = curve.get_derivatives(q - delta);
+        let [point2, tangent2, _] = curve.get_derivatives(q + delta);
+        for axis in 0..2 {
+            assert_abs_diff_eq!(
+                tangent[axis],
+                (point2[axis] - point1[axis]) / (2. * delta),
+                epsilon = 1e-6
+            );
+            assert_abs_diff_eq!(
+                second_derivative[axis],
+                (tangent2[axis] - tangent1[axis]) / (2. * delta),
+                epsilon = 1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn test_arc() {
+        let curve = Curve::Arc {
+            center: Position([1., 2.]),
+            radius: 2.,
+            start_angle: -PI / 2.,
+        };
+        let [point, tangent, _] = curve.get_derivatives(0.);
+        assert_abs_diff_eq!(point[0], 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(point[1], 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(tangent[0], 1., epsilon = 1e-12);
+        let [point, _, _] = curve.get_derivatives(PI);
+        assert_abs_diff_eq!(point[0], 3., epsilon = 1e-12);
+        assert_abs_diff_eq!(point[1], 2., epsilon = 1e-12);
+        assert_derivatives_consistent(&curve, 0.7);
+    }
+
+    #[test]
+    fn test_ellipse() {
+        let curve = Curve::Ellipse {
+            center: Position([1., 2.]),
+            radii: [3., 1.],
+        };
+        let [point, _, _] = curve.get_derivatives(PI / 2.);
+        assert_abs_diff_eq!(point[0], 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(point[1], 3., epsilon = 1e-12);
+        assert_derivatives_consistent(&curve, 0.7);
+    }
+
+    #[test]
+    fn test_spline() {
+        let points = vec![
+            Position([0., 0.]),
+            Position([1., 2.]),
+            Position([3., 1.]),
+            Position([4., 4.]),
+        ];
+        let curve = Curve::new_spline(points);
+        let [point, _, second_derivative] = curve.get_derivatives(0.);
+        assert_eq!(point, [0., 0.]);
+        assert_eq!(second_derivative, [0., 0.]);
+        assert_eq!(curve.get_derivatives(1.)[0], [1., 2.]);
+        assert_eq!(curve.get_derivatives(3.)[0], [4., 4.]);
+        for q in &[0.3, 1.5, 2.2, 2.9] {
+            assert_derivatives_consistent(&curve, *q);
+        }
+
+        // The second derivative is continuous across control points:
+        let [_, _, before] = curve.get_derivatives(1. - 1e-9);
+        let [_, _, after] = curve.get_derivatives(1. + 1e-9);
+        assert_abs_diff_eq!(before[0], after[0], epsilon = 1e-6);
+        assert_abs_diff_eq!(before[1], after[1], epsilon = 1e-6);
+
+        // Beyond the ends, the path continues in a straight line:
+        let [end_point, end_tangent, _] = curve.get_derivatives(3.);
+        let [point, tangent, second_derivative] = curve.get_derivatives(4.5);
+        assert_abs_diff_eq!(
+            point[0],
+            end_point[0] + 1.5 * end_tangent[0],
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            point[1],
+            end_point[1] + 1.5 * end_tangent[1],
+            epsilon = 1e-12
+        );
+        assert_eq!(tangent, end_tangent);
+        assert_eq!(second_derivative, [0., 0.]);
+        assert_derivatives_consistent(&curve, -0.5);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {"center": [1, 2], "radius": 3, "startAngle": 0.5, "type": "Arc"},
+              {"radii": [3, 1], "type": "Ellipse"},
+              {"points": [[0, 0], [1, 1], [2, 0]], "type": "Spline"}
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let curves = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Curve::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            curves,
+            vec![
+                Curve::Arc {
+                    center: Position([1., 2.]),
+                    radius: 3.,
+                    start_angle: 0.5
+                },
+                Curve::Ellipse {
+                    center: Position([0., 0.]),
+                    radii: [3., 1.]
+                },
+                Curve::new_spline(vec![
+                    Position([0., 0.]),
+                    Position([1., 1.]),
+                    Position([2., 0.])
+                ]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_errors() {
+        let json = r#"{"points": [[0, 0]], "type": "Spline"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Curve::from_json_value(&json_value).unwrap_err().to_string(),
+            r#"Expected at least two spline points; got {"points":[[0,0]],"type":"Spline"}"#
+        );
+
+        let json = r#"{"type": "Helix"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Curve::from_json_value(&json_value).unwrap_err().to_string(),
+            "Invalid curve type: Helix"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::json;
+use crate::Curve;
+use crate::Error;
+use crate::Frame;
+use crate::FrameBox;
+use crate::FrameId;
+use crate::Mat3;
+use crate::Position;
+use crate::Weight;
+
+/// Frame that slides along a curved path without rotating, like a bead on a wire.  Its
+/// coordinate is the curve's parameter.
+#[derive(Debug)]
+pub struct CurveFrame {
+    pub children: Vec<FrameBox>,
+    pub curve: Curve,
+    pub id: FrameId,
+    pub position: Position,
+    pub resistance: f64,
+    pub weights: Vec<Weight>,
+}
+
+impl CurveFrame {
+    pub fn new(id: FrameId, curve: Curve) -> Self {
+        Self {
+            children: Vec::new(),
+            curve,
+            id,
+            position: Position([0., 0.]),
+            resistance: 0.,
+            weights: Vec::new(),
+        }
+    }
+
+    pub fn set_position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn add_child(mut self, child: FrameBox) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn set_resistance(mut self, resistance: f64) -> Self {
+        self.resistance = resistance;
+        self
+    }
+
+    pub fn add_weight(mut self, weight: Weight) -> Self {
+        self.weights.push(weight);
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(CurveFrame {
+            children: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            curve: json::map_value_item(value, "curve", Curve::from_json_value)?,
+            id: json::map_value_item(value, "id", json::value_to_str)?.into(),
+            position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
+            resistance: json::map_obj_item_or_default(obj, "resistance", json::value_to_f64)?,
+            weights: json::obj_to_frame_weights(obj)?,
+        })
+    }
+}
+
+impl Frame for CurveFrame {
+    fn get_children(&self) -> &[FrameBox] {
+        &self.children
+    }
+
+    fn get_id(&self) -> &FrameId {
+        &self.id
+    }
+
+    fn get_resistance(&self) -> f64 {
+        self.resistance
+    }
+
+    fn get_weights(&self) -> &[Weight] {
+        &self.weights
+    }
+
+    fn get_local_pos_matrix(&self, qs: &[f64]) -> Mat3 {
+        let q = qs[0];
+        let [point, _, _] = self.curve.get_derivatives(q);
+        Mat3::new(
+            1.,
+            0.,
+            self.position.0[0] + point[0],
+            0.,
+            1.,
+            self.position.0[1] + point[1],
+            0.,
+            0.,
+            1.,
+        )
+    }
+
+    fn get_local_vel_matrix(&self, qs: &[f64], _index: usize) -> Mat3 {
+        let q = qs[0];
+        let [_, tangent, _] = self.curve.get_derivatives(q);
+        Mat3::new(0., 0., tangent[0], 0., 0., tangent[1], 0., 0., 0.)
+    }
+
+    fn get_local_accel_matrix(&self, qs: &[f64], _index1: usize, _index2: usize) -> Mat3 {
+        let q = qs[0];
+        let [_, _, second_derivative] = self.curve.get_derivatives(q);
+        Mat3::new(
+            0.,
+            0.,
+            second_derivative[0],
+            0.,
+            0.,
+            second_derivative[1],
+            0.,
+            0.,
+            0.,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RotationalFrame;
+
+    #[test]
+    fn test_new() {
+        let curve = Curve::Ellipse {
+            center: Position([0., 0.]),
+            radii: [2., 1.],
+        };
+        let frame = CurveFrame::new("a".into(), curve)
+            .set_position(Position([1., 2.]))
+            .set_resistance(0.5)
+            .add_child(Box::new(RotationalFrame::new("b".into())))
+            .add_weight(Weight::new(3.));
+        assert_eq!(frame.id, "a");
+        assert_eq!(
+            frame.curve,
+            Curve::Ellipse {
+                center: Position([0., 0.]),
+                radii: [2., 1.],
+            }
+        );
+        assert_eq!(frame.position, Position([1., 2.]));
+        assert_eq!(frame.resistance, 0.5);
+        assert_eq!(frame.children.len(), 1);
+        assert_eq!(frame.weights, vec![Weight::new(3.)]);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "curve": {
+                "radius": 2,
+                "type": "Arc"
+              },
+              "id": "a",
+              "position": [
+                1,
+                2
+              ],
+              "type": "CurveFrame"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let frame = CurveFrame::from_json_value(&json_value).unwrap();
+        assert_eq!(frame.id, "a");
+        assert_eq!(
+            frame.curve,
+            Curve::Arc {
+                center: Position([0., 0.]),
+                radius: 2.,
+                start_angle: 0.
+            }
+        );
+        assert_eq!(frame.position, Position([1., 2.]));
+        assert_eq!(frame.children.len(), 0);
+        assert_eq!(frame.weights.len(), 0);
+    }
+
+    #[test]
+    fn test_get_local_matrices() {
+        let curve = Curve::Ellipse {
+            center: Position([0., 0.]),
+            radii: [2., 1.],
+        };
+        let frame = CurveFrame::new("a".into(), curve).set_position(Position([1., 2.]));
+        assert_abs_diff_eq!(
+            frame.get_local_pos_matrix(&[0.]),
+            Mat3::new(
+                1., 0., 3., //
+                0., 1., 2., //
+                0., 0., 1., //
+            )
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_vel_matrix(&[0.], 0),
+            Mat3::new(
+                0., 0., 0., //
+                0., 0., 1., //
+                0., 0., 0., //
+            )
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_accel_matrix(&[0.], 0, 0),
+            Mat3::new(
+                0., 0., -2., //
+                0., 0., 0., //
+                0., 0., 0., //
+            )
+        );
+    }
+}
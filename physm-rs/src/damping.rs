@@ -0,0 +1,114 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+
+/// Linear damping over the generalized coordinates, which adds a generalized force of `-C * qd`
+/// on top of the frames' own `resistance` and the weights' drag.
+#[derive(Debug, PartialEq)]
+pub enum Damping {
+    /// Damping matrix over the coordinates of the given frames, in order (with each frame taking
+    /// up as many rows and columns as it has coordinates).  Off-diagonal terms couple the frames,
+    /// e.g. `[[c, -c], [-c, c]]` is a dashpot between two carts.
+    Matrix {
+        frame_ids: Vec<FrameId>,
+        matrix: nalgebra::DMatrix<f64>,
+    },
+    /// Rayleigh damping, `alpha * M`, which damps each mode of vibration with a damping ratio of
+    /// `alpha / (2 * omega)`.  Scenes have no stiffness matrix, so there's no stiffness-
+    /// proportional term.
+    Rayleigh { alpha: f64 },
+}
+
+impl Damping {
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "Matrix" => Damping::Matrix {
+                frame_ids: json::map_value_item(value, "frames", |value| {
+                    json::value_to_vec(value, json::value_to_frame_id)
+                })?,
+                matrix: json::map_value_item(value, "matrix", value_to_matrix)?,
+            },
+            "Rayleigh" => Damping::Rayleigh {
+                alpha: json::map_value_item(value, "alpha", json::value_to_f64)?,
+            },
+            _ => return Err(Error(format!("Invalid damping type: {}", type_name))),
+        })
+    }
+}
+
+fn value_to_matrix(value: &serde_json::Value) -> Result<nalgebra::DMatrix<f64>, Error> {
+    let get_error = || Error(format!("Expected square matrix; got {}", value));
+    let rows = value
+        .as_array()
+        .ok_or_else(get_error)?
+        .iter()
+        .map(|row| {
+            row.as_array()
+                .ok_or_else(get_error)?
+                .iter()
+                .map(json::value_to_f64)
+                .collect::<Result<Vec<f64>, Error>>()
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let size = rows.len();
+    if rows.iter().any(|row| row.len() != size) {
+        return Err(get_error());
+    }
+    Ok(nalgebra::DMatrix::from_fn(size, size, |row, col| {
+        rows[row][col]
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {"frames": ["a", "b"], "matrix": [[1, -1], [-1, 1]], "type": "Matrix"},
+              {"alpha": 0.5, "type": "Rayleigh"}
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let dampings = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Damping::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            dampings,
+            vec![
+                Damping::Matrix {
+                    frame_ids: vec!["a".into(), "b".into()],
+                    matrix: nalgebra::DMatrix::from_row_slice(2, 2, &[1., -1., -1., 1.]),
+                },
+                Damping::Rayleigh { alpha: 0.5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_errors() {
+        let json = r#"{"frames": ["a"], "matrix": [[1, 2]], "type": "Matrix"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Damping::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Expected square matrix; got [[1,2]]"
+        );
+
+        let json = r#"{"type": "Modal"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Damping::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Invalid damping type: Modal"
+        );
+    }
+}
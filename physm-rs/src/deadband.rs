@@ -0,0 +1,52 @@
+use crate::json;
+use crate::Backlash;
+use crate::Error;
+use crate::FrameId;
+
+/// Loose joint which holds a frame's coordinate near a rest `position`, but only once it's moved
+/// further than half the backlash's width from it, e.g. a pin rattling in an oversized hole.
+#[derive(Debug, PartialEq)]
+pub struct Deadband {
+    pub backlash: Backlash,
+    pub frame_id: FrameId,
+    pub position: f64,
+}
+
+impl Deadband {
+    pub fn new(frame_id: FrameId, backlash: Backlash) -> Self {
+        Self {
+            backlash,
+            frame_id,
+            position: 0.,
+        }
+    }
+
+    pub fn set_position(mut self, position: f64) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Deadband {
+            backlash: Backlash::from_json_value(value)?,
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            position: json::map_obj_item_or_default(obj, "position", json::value_to_f64)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"{"frame": "a", "position": 0.5, "stiffness": 1000, "width": 0.1}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Deadband::from_json_value(&json_value).unwrap(),
+            Deadband::new("a".into(), Backlash::new(0.1, 1000.)).set_position(0.5)
+        );
+    }
+}
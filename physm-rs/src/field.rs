@@ -0,0 +1,172 @@
+use crate::json;
+use crate::Error;
+use crate::Vec3;
+
+/// Non-uniform force field acting on every weight in the scene, evaluated at each weight's world
+/// position (and velocity, for the rotating reference frame).
+#[derive(Debug, PartialEq)]
+pub enum Field {
+    /// Inverse-square attraction towards a point mass, with `strength` being the gravitational
+    /// parameter (`G * M`).  A nonzero `softening` length keeps the force finite near the center.
+    PointMass {
+        position: Vec3,
+        softening: f64,
+        strength: f64,
+    },
+    /// Fictitious centrifugal and Coriolis forces seen when the whole scene is simulated in a
+    /// reference frame rotating about `center` at a constant `angular_velocity`.
+    Rotating { angular_velocity: f64, center: Vec3 },
+}
+
+impl Field {
+    pub fn get_force(&self, mass: f64, pos: &Vec3, vel: &Vec3) -> Vec3 {
+        match self {
+            Field::PointMass {
+                position,
+                softening,
+                strength,
+            } => {
+                let offset = Vec3::new(position[0] - pos[0], position[1] - pos[1], 0.);
+                let distance_squared = offset.norm_squared() + softening * softening;
+                match distance_squared > 0. {
+                    true => mass * strength * offset / distance_squared.powf(1.5),
+                    false => Vec3::zeros(),
+                }
+            }
+            Field::Rotating {
+                angular_velocity,
+                center,
+            } => {
+                let radius_vec = Vec3::new(pos[0] - center[0], pos[1] - center[1], 0.);
+                let centrifugal_force = mass * angular_velocity * angular_velocity * radius_vec;
+                let coriolis_force = 2. * mass * angular_velocity * Vec3::new(vel[1], -vel[0], 0.);
+                centrifugal_force + coriolis_force
+            }
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "PointMass" => Field::PointMass {
+                position: json::map_obj_item_or_default(obj, "position", json::value_to_vec3)?,
+                softening: json::map_obj_item_or_default(obj, "softening", json::value_to_f64)?,
+                strength: json::map_value_item(value, "strength", json::value_to_f64)?,
+            },
+            "Rotating" => Field::Rotating {
+                angular_velocity: json::map_value_item(
+                    value,
+                    "angularVelocity",
+                    json::value_to_f64,
+                )?,
+                center: json::map_obj_item_or_default(obj, "center", json::value_to_vec3)?,
+            },
+            _ => return Err(Error(format!("Invalid field type: {}", type_name))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_mass() {
+        let field = Field::PointMass {
+            position: Vec3::new(1., 1., 0.),
+            softening: 0.,
+            strength: 50.,
+        };
+        let pos = Vec3::new(4., 5., 1.);
+        let vel = Vec3::new(3., 0., 0.);
+        assert_abs_diff_eq!(
+            field.get_force(2., &pos, &vel),
+            Vec3::new(-3., -4., 0.) * 2. * 50. / 125.,
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            field.get_force(2., &Vec3::new(1., 1., 1.), &vel),
+            Vec3::zeros()
+        );
+
+        let field = Field::PointMass {
+            position: Vec3::new(1., 1., 0.),
+            softening: 3.,
+            strength: 50.,
+        };
+        assert_abs_diff_eq!(
+            field.get_force(2., &Vec3::new(1., 5., 1.), &vel),
+            Vec3::new(0., -4., 0.) * 2. * 50. / 125.,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_rotating() {
+        let field = Field::Rotating {
+            angular_velocity: 2.,
+            center: Vec3::new(1., 0., 0.),
+        };
+        let pos = Vec3::new(4., 0., 1.);
+        // Centrifugal force pushes outwards:
+        assert_abs_diff_eq!(
+            field.get_force(3., &pos, &Vec3::zeros()),
+            Vec3::new(36., 0., 0.)
+        );
+        // The Coriolis force deflects counter-clockwise-rotating observers' motion to the right:
+        assert_abs_diff_eq!(
+            field.get_force(3., &Vec3::new(1., 0., 1.), &Vec3::new(0., 5., 0.)),
+            Vec3::new(60., 0., 0.)
+        );
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {
+                "position": [1, 2],
+                "softening": 0.5,
+                "strength": 40,
+                "type": "PointMass"
+              },
+              {
+                "angularVelocity": 1.5,
+                "type": "Rotating"
+              }
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let fields = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Field::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                Field::PointMass {
+                    position: Vec3::new(1., 2., 0.),
+                    softening: 0.5,
+                    strength: 40.,
+                },
+                Field::Rotating {
+                    angular_velocity: 1.5,
+                    center: Vec3::zeros(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_invalid_type() {
+        let json = r#"{"type": "Magnetic"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Field::from_json_value(&json_value).unwrap_err().to_string(),
+            "Invalid field type: Magnetic"
+        );
+    }
+}
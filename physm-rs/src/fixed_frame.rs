@@ -0,0 +1,185 @@
+use crate::json;
+use crate::Error;
+use crate::Frame;
+use crate::FrameBox;
+use crate::FrameId;
+use crate::Mat3;
+use crate::Position;
+use crate::Weight;
+
+/// Frame with a constant transform relative to its parent, for rigidly attaching weights and
+/// sub-assemblies at an offset and angle.  It has no coordinates of its own, so its weights simply
+/// move with its parent.
+#[derive(Debug)]
+pub struct FixedFrame {
+    pub angle: f64,
+    pub children: Vec<FrameBox>,
+    pub id: FrameId,
+    pub position: Position,
+    pub weights: Vec<Weight>,
+}
+
+impl FixedFrame {
+    pub fn new(id: FrameId) -> Self {
+        Self {
+            angle: 0.,
+            children: Vec::new(),
+            id,
+            position: Position([0., 0.]),
+            weights: Vec::new(),
+        }
+    }
+
+    pub fn set_angle(mut self, angle: f64) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    pub fn set_position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn add_child(mut self, child: FrameBox) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn add_weight(mut self, weight: Weight) -> Self {
+        self.weights.push(weight);
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(FixedFrame {
+            angle: json::map_obj_item_or_default(obj, "angle", json::value_to_f64)?,
+            children: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            id: json::map_value_item(value, "id", json::value_to_str)?.into(),
+            position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
+            weights: json::obj_to_frame_weights(obj)?,
+        })
+    }
+}
+
+impl Frame for FixedFrame {
+    fn get_children(&self) -> &[FrameBox] {
+        &self.children
+    }
+
+    fn get_id(&self) -> &FrameId {
+        &self.id
+    }
+
+    fn get_resistance(&self) -> f64 {
+        0.
+    }
+
+    fn get_weights(&self) -> &[Weight] {
+        &self.weights
+    }
+
+    fn get_coordinate_count(&self) -> usize {
+        0
+    }
+
+    fn get_local_pos_matrix(&self, _qs: &[f64]) -> Mat3 {
+        Mat3::new(
+            self.angle.cos(),
+            -self.angle.sin(),
+            self.position.0[0],
+            self.angle.sin(),
+            self.angle.cos(),
+            self.position.0[1],
+            0.,
+            0.,
+            1.,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::RotationalFrame;
+
+    #[test]
+    fn test_new() {
+        let frame = FixedFrame::new("a".into());
+        assert_eq!(frame.id, "a");
+        assert_eq!(frame.angle, 0.);
+        assert_eq!(frame.position, Position([0., 0.]));
+        assert_eq!(frame.children.len(), 0);
+        assert_eq!(frame.weights.len(), 0);
+        let frame = frame
+            .set_angle(PI)
+            .set_position(Position([2., 3.]))
+            .add_child(Box::new(RotationalFrame::new("b".into())))
+            .add_weight(Weight::new(12.));
+        assert_eq!(frame.angle, PI);
+        assert_eq!(frame.position, Position([2., 3.]));
+        assert_eq!(frame.children.len(), 1);
+        assert_eq!(frame.weights, vec![Weight::new(12.)]);
+        assert_eq!(frame.get_coordinate_count(), 0);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "angle": 1.5,
+              "frames": [
+                {
+                  "id": "b",
+                  "type": "RotationalFrame"
+                }
+              ],
+              "id": "a",
+              "position": [
+                5,
+                6
+              ],
+              "type": "FixedFrame",
+              "weights": [
+                {
+                  "drag": 0,
+                  "mass": 3,
+                  "position": [
+                    0,
+                    0
+                  ]
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let frame = FixedFrame::from_json_value(&json_value).unwrap();
+        assert_eq!(frame.angle, 1.5);
+        assert_eq!(frame.id, "a");
+        assert_eq!(frame.position, Position([5., 6.]));
+        assert_eq!(
+            format!("{:?}", frame.children),
+            format!("{:?}", vec![Box::new(RotationalFrame::new("b".into()))]),
+        );
+        assert_eq!(frame.weights, vec![Weight::new(3.)]);
+    }
+
+    #[test]
+    fn test_get_local_matrices() {
+        let frame = FixedFrame::new("a".into())
+            .set_angle(PI / 2.)
+            .set_position(Position([3., 4.]));
+        assert_abs_diff_eq!(
+            frame.get_local_pos_matrix(&[]),
+            Mat3::new(
+                0., -1., 3., //
+                1., 0., 4., //
+                0., 0., 1., //
+            ),
+            epsilon = 1e-12
+        );
+    }
+}
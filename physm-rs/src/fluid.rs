@@ -0,0 +1,172 @@
+use std::f64::consts::PI;
+
+use crate::json;
+use crate::Error;
+use crate::Vec3;
+
+/// Still body of fluid filling everything below a horizontal surface at height `level`, e.g. a
+/// pond for floating buoys or the sea for a rolling ship.  Weights with a displaced `volume` are
+/// buoyed up by the weight of the fluid they displace, and the submerged share of each weight
+/// (whatever its volume) is slowed by the fluid's linear `drag`.
+///
+/// Weights with a `radius` are treated as discs that sink into the fluid gradually, while ones
+/// without are either fully submerged or not at all.
+#[derive(Debug, PartialEq)]
+pub struct Fluid {
+    pub density: f64,
+    pub drag: f64,
+    pub level: f64,
+}
+
+impl Fluid {
+    pub fn new(density: f64) -> Self {
+        Self {
+            density,
+            drag: 0.,
+            level: 0.,
+        }
+    }
+
+    pub fn set_drag(mut self, drag: f64) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    pub fn set_level(mut self, level: f64) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Returns the share of a weight with the given radius at `pos` that's below the surface,
+    /// from the area of the disc's submerged segment.
+    pub fn get_submerged_fraction(&self, pos: &Vec3, radius: f64) -> f64 {
+        let depth = self.level - pos[1];
+        if radius <= 0. {
+            return match depth > 0. {
+                true => 1.,
+                false => 0.,
+            };
+        }
+        let height = (depth + radius).clamp(0., 2. * radius);
+        let offset = radius - height;
+        let area = radius * radius * (offset / radius).acos()
+            - offset * (2. * radius * height - height * height).sqrt();
+        area / (PI * radius * radius)
+    }
+
+    /// Returns the buoyancy and drag forces on a weight with the given displaced volume and
+    /// radius, at the given world-space position and velocity.
+    pub fn get_force(
+        &self,
+        gravity: &Vec3,
+        volume: f64,
+        radius: f64,
+        pos: &Vec3,
+        vel: &Vec3,
+    ) -> Vec3 {
+        let fraction = self.get_submerged_fraction(pos, radius);
+        -(self.density * volume * fraction) * gravity - self.drag * fraction * vel
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Fluid {
+            density: json::map_value_item(value, "density", json::value_to_f64)?,
+            drag: json::map_obj_item_or_default(obj, "drag", json::value_to_f64)?,
+            level: json::map_obj_item_or_default(obj, "level", json::value_to_f64)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_submerged_fraction() {
+        let fluid = Fluid::new(1000.).set_level(1.);
+        assert_eq!(
+            fluid.get_submerged_fraction(&Vec3::new(0., 0.5, 1.), 0.),
+            1.
+        );
+        assert_eq!(
+            fluid.get_submerged_fraction(&Vec3::new(0., 1.5, 1.), 0.),
+            0.
+        );
+        assert_eq!(
+            fluid.get_submerged_fraction(&Vec3::new(0., 2., 1.), 0.5),
+            0.
+        );
+        assert_abs_diff_eq!(
+            fluid.get_submerged_fraction(&Vec3::new(0., 1., 1.), 0.5),
+            0.5,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            fluid.get_submerged_fraction(&Vec3::new(0., 0., 1.), 0.5),
+            1.,
+            epsilon = 1e-12
+        );
+        // A disc dipped to a quarter of its diameter has a 120 degree sector underwater, minus
+        // the triangle above the chord:
+        let fraction = fluid.get_submerged_fraction(&Vec3::new(0., 1.5, 1.), 1.);
+        assert_abs_diff_eq!(
+            fraction,
+            (PI / 3. - 0.75_f64.sqrt() / 2.) / PI,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            fluid.get_submerged_fraction(&Vec3::new(0., 0.5, 1.), 1.),
+            1. - fraction,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_get_force() {
+        let fluid = Fluid::new(1000.).set_drag(2.);
+        let gravity = Vec3::new(0., -10., 0.);
+        assert_abs_diff_eq!(
+            fluid.get_force(
+                &gravity,
+                0.002,
+                0.,
+                &Vec3::new(0., -1., 1.),
+                &Vec3::new(1., 0., 0.)
+            ),
+            Vec3::new(-2., 20., 0.),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            fluid.get_force(
+                &gravity,
+                0.002,
+                0.1,
+                &Vec3::new(0., 0., 1.),
+                &Vec3::new(1., 0., 0.)
+            ),
+            Vec3::new(-1., 10., 0.),
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            fluid.get_force(
+                &gravity,
+                0.002,
+                0.1,
+                &Vec3::new(0., 1., 1.),
+                &Vec3::new(1., 0., 0.)
+            ),
+            Vec3::zeros()
+        );
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"{"density": 1000, "drag": 0.5, "level": -2}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Fluid::from_json_value(&json_value).unwrap(),
+            Fluid::new(1000.).set_drag(0.5).set_level(-2.)
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::f64::consts::PI;
+
+use crate::json;
+use crate::utils;
+use crate::Error;
+use crate::FrameId;
+
+#[derive(Debug, PartialEq)]
+pub enum ForceProfile {
+    /// Zero until `time`, then `magnitude`.
+    Step { magnitude: f64, time: f64 },
+    /// Zero until `start_time`, then rises linearly to reach `magnitude` at `end_time`.
+    Ramp {
+        end_time: f64,
+        magnitude: f64,
+        start_time: f64,
+    },
+    Sinusoid {
+        amplitude: f64,
+        frequency: f64,
+        phase: f64,
+    },
+    /// Sinusoid whose frequency sweeps linearly from `start_frequency` to `end_frequency` over
+    /// `duration` (which must be positive), after which the force drops to zero.
+    Chirp {
+        amplitude: f64,
+        duration: f64,
+        end_frequency: f64,
+        start_frequency: f64,
+    },
+    /// Piecewise-linear interpolation between `(time, force)` points, holding the first and last
+    /// values outside of the table's time range.
+    Table { points: Vec<(f64, f64)> },
+}
+
+impl ForceProfile {
+    pub fn get_force(&self, time: f64) -> f64 {
+        match self {
+            ForceProfile::Step {
+                magnitude,
+                time: step_time,
+            } => match time >= *step_time {
+                true => *magnitude,
+                false => 0.,
+            },
+            ForceProfile::Ramp {
+                end_time,
+                magnitude,
+                start_time,
+            } => {
+                if time <= *start_time {
+                    0.
+                } else if time >= *end_time {
+                    *magnitude
+                } else {
+                    magnitude * (time - start_time) / (end_time - start_time)
+                }
+            }
+            ForceProfile::Sinusoid {
+                amplitude,
+                frequency,
+                phase,
+            } => amplitude * (2. * PI * frequency * time + phase).sin(),
+            ForceProfile::Chirp {
+                amplitude,
+                duration,
+                end_frequency,
+                start_frequency,
+            } => match time >= 0. && time <= *duration {
+                true => {
+                    let sweep_rate = (end_frequency - start_frequency) / duration;
+                    let cycles = start_frequency * time + sweep_rate * time * time / 2.;
+                    amplitude * (2. * PI * cycles).sin()
+                }
+                false => 0.,
+            },
+            ForceProfile::Table { points } => {
+                utils::interpolate(points, time).unwrap_or(points[points.len() - 1].1)
+            }
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "Step" => ForceProfile::Step {
+                magnitude: json::map_value_item(value, "magnitude", json::value_to_f64)?,
+                time: json::map_obj_item_or_default(obj, "time", json::value_to_f64)?,
+            },
+            "Ramp" => ForceProfile::Ramp {
+                end_time: json::map_value_item(value, "endTime", json::value_to_f64)?,
+                magnitude: json::map_value_item(value, "magnitude", json::value_to_f64)?,
+                start_time: json::map_obj_item_or_default(obj, "startTime", json::value_to_f64)?,
+            },
+            "Sinusoid" => ForceProfile::Sinusoid {
+                amplitude: json::map_value_item(value, "amplitude", json::value_to_f64)?,
+                frequency: json::map_value_item(value, "frequency", json::value_to_f64)?,
+                phase: json::map_obj_item_or_default(obj, "phase", json::value_to_f64)?,
+            },
+            "Chirp" => {
+                let duration = json::map_value_item(value, "duration", json::value_to_f64)?;
+                if duration <= 0. {
+                    return Err(Error(format!(
+                        "Expected chirp with a positive duration; got {}",
+                        value
+                    )));
+                }
+                ForceProfile::Chirp {
+                    amplitude: json::map_value_item(value, "amplitude", json::value_to_f64)?,
+                    duration,
+                    end_frequency: json::map_value_item(value, "endFrequency", json::value_to_f64)?,
+                    start_frequency: json::map_value_item(
+                        value,
+                        "startFrequency",
+                        json::value_to_f64,
+                    )?,
+                }
+            }
+            "Table" => {
+                let points = json::map_value_item(value, "points", |value| {
+                    json::value_to_vec(value, json::value_to_point)
+                })?;
+                if points.is_empty() || points.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+                    return Err(Error(format!(
+                        "Expected non-empty table with increasing times; got {}",
+                        value
+                    )));
+                }
+                ForceProfile::Table { points }
+            }
+            _ => return Err(Error(format!("Invalid force profile type: {}", type_name))),
+        })
+    }
+}
+
+/// Generalized force applied to a frame according to a function of simulation time.
+#[derive(Debug, PartialEq)]
+pub struct ForceSchedule {
+    pub frame_id: FrameId,
+    pub profile: ForceProfile,
+}
+
+impl ForceSchedule {
+    pub fn new(frame_id: FrameId, profile: ForceProfile) -> Self {
+        Self { frame_id, profile }
+    }
+
+    pub fn get_force(&self, time: f64) -> f64 {
+        self.profile.get_force(time)
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        Ok(ForceSchedule {
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            profile: ForceProfile::from_json_value(value)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        let profile = ForceProfile::Step {
+            magnitude: 3.,
+            time: 1.,
+        };
+        assert_eq!(profile.get_force(0.5), 0.);
+        assert_eq!(profile.get_force(1.), 3.);
+        assert_eq!(profile.get_force(5.), 3.);
+    }
+
+    #[test]
+    fn test_ramp() {
+        let profile = ForceProfile::Ramp {
+            end_time: 3.,
+            magnitude: 4.,
+            start_time: 1.,
+        };
+        assert_eq!(profile.get_force(0.), 0.);
+        assert_eq!(profile.get_force(1.5), 1.);
+        assert_eq!(profile.get_force(3.), 4.);
+        assert_eq!(profile.get_force(10.), 4.);
+    }
+
+    #[test]
+    fn test_sinusoid() {
+        let profile = ForceProfile::Sinusoid {
+            amplitude: 2.,
+            frequency: 0.25,
+            phase: PI / 2.,
+        };
+        assert_abs_diff_eq!(profile.get_force(0.), 2.);
+        assert_abs_diff_eq!(profile.get_force(1.), 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(profile.get_force(2.), -2.);
+    }
+
+    #[test]
+    fn test_chirp() {
+        let profile = ForceProfile::Chirp {
+            amplitude: 2.,
+            duration: 4.,
+            end_frequency: 1.,
+            start_frequency: 0.,
+        };
+        // The phase grows quadratically: `cycles = t^2 / 8`.
+        assert_abs_diff_eq!(profile.get_force(0.), 0.);
+        assert_abs_diff_eq!(profile.get_force(2_f64.sqrt()), 2., epsilon = 1e-12);
+        assert_abs_diff_eq!(profile.get_force(2.), 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(profile.get_force(4.), 0., epsilon = 1e-12);
+        assert_eq!(profile.get_force(4.5), 0.);
+        assert_eq!(profile.get_force(-1.), 0.);
+    }
+
+    #[test]
+    fn test_table() {
+        let profile = ForceProfile::Table {
+            points: vec![(1., 2.), (2., 4.), (4., 0.)],
+        };
+        assert_eq!(profile.get_force(0.), 2.);
+        assert_eq!(profile.get_force(1.), 2.);
+        assert_eq!(profile.get_force(1.5), 3.);
+        assert_eq!(profile.get_force(3.), 2.);
+        assert_eq!(profile.get_force(4.), 0.);
+        assert_eq!(profile.get_force(9.), 0.);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {"frame": "a", "magnitude": 3, "time": 1, "type": "Step"},
+              {"endTime": 3, "frame": "a", "magnitude": 4, "type": "Ramp"},
+              {"amplitude": 2, "frame": "a", "frequency": 0.5, "type": "Sinusoid"},
+              {
+                "amplitude": 2,
+                "duration": 4,
+                "endFrequency": 1,
+                "frame": "a",
+                "startFrequency": 0.1,
+                "type": "Chirp"
+              },
+              {"frame": "b", "points": [[0, 1], [2, 3]], "type": "Table"}
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let schedules = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(ForceSchedule::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            schedules,
+            vec![
+                ForceSchedule::new(
+                    "a".into(),
+                    ForceProfile::Step {
+                        magnitude: 3.,
+                        time: 1.
+                    }
+                ),
+                ForceSchedule::new(
+                    "a".into(),
+                    ForceProfile::Ramp {
+                        end_time: 3.,
+                        magnitude: 4.,
+                        start_time: 0.
+                    }
+                ),
+                ForceSchedule::new(
+                    "a".into(),
+                    ForceProfile::Sinusoid {
+                        amplitude: 2.,
+                        frequency: 0.5,
+                        phase: 0.
+                    }
+                ),
+                ForceSchedule::new(
+                    "a".into(),
+                    ForceProfile::Chirp {
+                        amplitude: 2.,
+                        duration: 4.,
+                        end_frequency: 1.,
+                        start_frequency: 0.1
+                    }
+                ),
+                ForceSchedule::new(
+                    "b".into(),
+                    ForceProfile::Table {
+                        points: vec![(0., 1.), (2., 3.)]
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_bad_table() {
+        let json = r#"{"frame": "a", "points": [[2, 1], [1, 3]], "type": "Table"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            ForceSchedule::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected non-empty table with increasing times; got {"frame":"a","points":[[2,1],[1,3]],"type":"Table"}"#
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_bad_chirp() {
+        let json = r#"
+            {
+              "amplitude": 1,
+              "duration": 0,
+              "endFrequency": 2,
+              "frame": "a",
+              "startFrequency": 1,
+              "type": "Chirp"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            ForceSchedule::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected chirp with a positive duration; got {"amplitude":1,"duration":0,"endFrequency":2,"frame":"a","startFrequency":1,"type":"Chirp"}"#
+        );
+    }
+}
@@ -14,15 +14,24 @@ pub trait Frame: Debug {
 
     fn get_weights(&self) -> &[Weight];
 
-    fn get_local_pos_matrix(&self, _q: f64) -> Mat3 {
+    /// Number of generalized coordinates (degrees of freedom) that the frame contributes; the
+    /// `qs` passed to the matrix methods hold that many values.
+    fn get_coordinate_count(&self) -> usize {
+        1
+    }
+
+    fn get_local_pos_matrix(&self, _qs: &[f64]) -> Mat3 {
         Mat3::identity()
     }
 
-    fn get_local_vel_matrix(&self, _q: f64) -> Mat3 {
+    /// Partial derivative of the local position matrix with respect to coordinate `index`.
+    fn get_local_vel_matrix(&self, _qs: &[f64], _index: usize) -> Mat3 {
         Mat3::zeros()
     }
 
-    fn get_local_accel_matrix(&self, _q: f64) -> Mat3 {
+    /// Second partial derivative of the local position matrix with respect to coordinates
+    /// `index1` and `index2`.
+    fn get_local_accel_matrix(&self, _qs: &[f64], _index1: usize, _index2: usize) -> Mat3 {
         Mat3::zeros()
     }
 }
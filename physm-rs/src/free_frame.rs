@@ -0,0 +1,207 @@
+use crate::json;
+use crate::Error;
+use crate::Frame;
+use crate::FrameBox;
+use crate::FrameId;
+use crate::Mat3;
+use crate::Position;
+use crate::Weight;
+
+/// Frame that moves freely in the plane, like a projectile or a thrown juggling club.  Its
+/// coordinates are `[x, y, angle]`, with the translation applied in the parent's axes before the
+/// rotation.  If nothing on the frame has rotational inertia (e.g. a single point mass at its
+/// origin), the angle just keeps turning at its initial rate.
+#[derive(Debug)]
+pub struct FreeFrame {
+    pub children: Vec<FrameBox>,
+    pub id: FrameId,
+    pub position: Position,
+    pub resistance: f64,
+    pub weights: Vec<Weight>,
+}
+
+impl FreeFrame {
+    pub fn new(id: FrameId) -> Self {
+        Self {
+            children: Vec::new(),
+            id,
+            position: Position([0., 0.]),
+            resistance: 0.,
+            weights: Vec::new(),
+        }
+    }
+
+    pub fn add_child(mut self, child: FrameBox) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn set_position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn set_resistance(mut self, resistance: f64) -> Self {
+        self.resistance = resistance;
+        self
+    }
+
+    pub fn add_weight(mut self, weight: Weight) -> Self {
+        self.weights.push(weight);
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(FreeFrame {
+            children: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            id: json::map_value_item(value, "id", json::value_to_str)?.into(),
+            position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
+            resistance: json::map_obj_item_or_default(obj, "resistance", json::value_to_f64)?,
+            weights: json::obj_to_frame_weights(obj)?,
+        })
+    }
+}
+
+impl Frame for FreeFrame {
+    fn get_children(&self) -> &[FrameBox] {
+        &self.children
+    }
+
+    fn get_id(&self) -> &FrameId {
+        &self.id
+    }
+
+    fn get_resistance(&self) -> f64 {
+        self.resistance
+    }
+
+    fn get_weights(&self) -> &[Weight] {
+        &self.weights
+    }
+
+    fn get_coordinate_count(&self) -> usize {
+        3
+    }
+
+    fn get_local_pos_matrix(&self, qs: &[f64]) -> Mat3 {
+        let (sin, cos) = qs[2].sin_cos();
+        Mat3::new(
+            cos,
+            -sin,
+            self.position.0[0] + qs[0],
+            sin,
+            cos,
+            self.position.0[1] + qs[1],
+            0.,
+            0.,
+            1.,
+        )
+    }
+
+    fn get_local_vel_matrix(&self, qs: &[f64], index: usize) -> Mat3 {
+        let (sin, cos) = qs[2].sin_cos();
+        match index {
+            0 => Mat3::new(0., 0., 1., 0., 0., 0., 0., 0., 0.),
+            1 => Mat3::new(0., 0., 0., 0., 0., 1., 0., 0., 0.),
+            _ => Mat3::new(-sin, -cos, 0., cos, -sin, 0., 0., 0., 0.),
+        }
+    }
+
+    fn get_local_accel_matrix(&self, qs: &[f64], index1: usize, index2: usize) -> Mat3 {
+        // Only the angle appears non-linearly:
+        let (sin, cos) = qs[2].sin_cos();
+        match (index1, index2) {
+            (2, 2) => Mat3::new(-cos, sin, 0., -sin, -cos, 0., 0., 0., 0.),
+            _ => Mat3::zeros(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::RotationalFrame;
+
+    #[test]
+    fn test_new() {
+        let frame = FreeFrame::new("a".into())
+            .set_position(Position([1., 2.]))
+            .set_resistance(0.5)
+            .add_child(Box::new(RotationalFrame::new("b".into())))
+            .add_weight(Weight::new(3.));
+        assert_eq!(frame.id, "a");
+        assert_eq!(frame.position, Position([1., 2.]));
+        assert_eq!(frame.resistance, 0.5);
+        assert_eq!(frame.children.len(), 1);
+        assert_eq!(frame.weights, vec![Weight::new(3.)]);
+        assert_eq!(frame.get_coordinate_count(), 3);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "id": "a",
+              "position": [
+                1,
+                2
+              ],
+              "resistance": 0.5,
+              "type": "FreeFrame"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let frame = FreeFrame::from_json_value(&json_value).unwrap();
+        assert_eq!(frame.id, "a");
+        assert_eq!(frame.position, Position([1., 2.]));
+        assert_eq!(frame.resistance, 0.5);
+        assert_eq!(frame.children.len(), 0);
+        assert_eq!(frame.weights.len(), 0);
+    }
+
+    #[test]
+    fn test_get_local_matrices() {
+        let frame = FreeFrame::new("a".into()).set_position(Position([1., 2.]));
+        let qs = [3., 4., PI / 2.];
+        assert_abs_diff_eq!(
+            frame.get_local_pos_matrix(&qs),
+            Mat3::new(
+                0., -1., 4., //
+                1., 0., 6., //
+                0., 0., 1., //
+            ),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_vel_matrix(&qs, 1),
+            Mat3::new(
+                0., 0., 0., //
+                0., 0., 1., //
+                0., 0., 0., //
+            )
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_vel_matrix(&qs, 2),
+            Mat3::new(
+                -1., 0., 0., //
+                0., -1., 0., //
+                0., 0., 0., //
+            ),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_accel_matrix(&qs, 2, 2),
+            Mat3::new(
+                0., 1., 0., //
+                -1., 0., 0., //
+                0., 0., 0., //
+            ),
+            epsilon = 1e-12
+        );
+        assert_eq!(frame.get_local_accel_matrix(&qs, 0, 2), Mat3::zeros());
+    }
+}
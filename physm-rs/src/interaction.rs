@@ -0,0 +1,265 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+use crate::Vec3;
+use crate::Weight;
+
+/// Radial force between a pair of weights as a function of the distance between them, where
+/// positive forces push the weights apart.
+#[derive(Debug, PartialEq)]
+pub enum InteractionLaw {
+    /// Mutual gravitational attraction, `constant * m1 * m2 / r^2`.
+    Gravitation { constant: f64 },
+    /// `strength / r^2` regardless of mass, which repels for a positive `strength` and attracts
+    /// for a negative one (e.g. electrostatics between like or unlike charges).
+    InverseSquare { strength: f64 },
+    /// Lennard-Jones force, which repels within `radius` (where the potential crosses zero) and
+    /// attracts beyond it, with `depth` being the depth of the potential well.
+    LennardJones { depth: f64, radius: f64 },
+    /// Custom potential, linearly interpolated between `(distance, potential)` points, whose
+    /// force is the negative of its slope (so it's constant along each segment).  The first
+    /// segment's slope carries on to shorter distances, so the force there stays at that
+    /// segment's force instead of growing; a steep first segment makes a hard core.  The
+    /// potential is flat beyond the last point, so there's no force there.
+    Potential { points: Vec<(f64, f64)> },
+}
+
+impl InteractionLaw {
+    pub fn get_force(&self, distance: f64, mass1: f64, mass2: f64) -> f64 {
+        match self {
+            InteractionLaw::Gravitation { constant } => {
+                -constant * mass1 * mass2 / (distance * distance)
+            }
+            InteractionLaw::InverseSquare { strength } => strength / (distance * distance),
+            InteractionLaw::LennardJones { depth, radius } => {
+                let ratio6 = (radius / distance).powi(6);
+                24. * depth * (2. * ratio6 * ratio6 - ratio6) / distance
+            }
+            InteractionLaw::Potential { points } => {
+                match points
+                    .iter()
+                    .position(|(point_distance, _)| *point_distance > distance)
+                {
+                    Some(index) => {
+                        let (distance1, potential1) = points[index.max(1) - 1];
+                        let (distance2, potential2) = points[index.max(1)];
+                        -(potential2 - potential1) / (distance2 - distance1)
+                    }
+                    None => 0.,
+                }
+            }
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "Gravitation" => InteractionLaw::Gravitation {
+                constant: json::map_value_item(value, "constant", json::value_to_f64)?,
+            },
+            "InverseSquare" => InteractionLaw::InverseSquare {
+                strength: json::map_value_item(value, "strength", json::value_to_f64)?,
+            },
+            "LennardJones" => InteractionLaw::LennardJones {
+                depth: json::map_value_item(value, "depth", json::value_to_f64)?,
+                radius: json::map_value_item(value, "radius", json::value_to_f64)?,
+            },
+            "Potential" => {
+                let points = json::map_value_item(value, "points", |value| {
+                    json::value_to_vec(value, json::value_to_point)
+                })?;
+                if points.len() < 2 || points.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+                    return Err(Error(format!(
+                        "Expected at least two potential points with increasing distances; got {}",
+                        value
+                    )));
+                }
+                InteractionLaw::Potential { points }
+            }
+            _ => return Err(Error(format!("Invalid interaction type: {}", type_name))),
+        })
+    }
+}
+
+/// Force acting between every pair of weights that are on the given frames or carry any of the
+/// given tags (or between all of the scene's weights, if neither is given).  A nonzero `softening`
+/// length keeps the force finite when weights pass close to each other.
+#[derive(Debug, PartialEq)]
+pub struct Interaction {
+    pub frame_ids: Vec<FrameId>,
+    pub law: InteractionLaw,
+    pub softening: f64,
+    pub tags: Vec<String>,
+}
+
+impl Interaction {
+    pub fn new(law: InteractionLaw) -> Self {
+        Self {
+            frame_ids: Vec::new(),
+            law,
+            softening: 0.,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn add_frame_id(mut self, frame_id: FrameId) -> Self {
+        self.frame_ids.push(frame_id);
+        self
+    }
+
+    pub fn set_softening(mut self, softening: f64) -> Self {
+        self.softening = softening;
+        self
+    }
+
+    pub fn add_tag(mut self, tag: String) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Returns whether the interaction acts on the given weight of the given frame.
+    pub fn applies_to(&self, frame_id: &FrameId, weight: &Weight) -> bool {
+        (self.frame_ids.is_empty() && self.tags.is_empty())
+            || self.frame_ids.contains(frame_id)
+            || weight.tags.iter().any(|tag| self.tags.contains(tag))
+    }
+
+    /// Returns the force that a weight at `pos1` exerts on one at `pos2`.
+    pub fn get_force(&self, pos1: &Vec3, mass1: f64, pos2: &Vec3, mass2: f64) -> Vec3 {
+        let offset = Vec3::new(pos2[0] - pos1[0], pos2[1] - pos1[1], 0.);
+        let distance = (offset.norm_squared() + self.softening * self.softening).sqrt();
+        match distance > 0. {
+            true => self.law.get_force(distance, mass1, mass2) * offset / distance,
+            false => Vec3::zeros(),
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Interaction {
+            frame_ids: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame_id)
+            })?,
+            law: InteractionLaw::from_json_value(value)?,
+            softening: json::map_obj_item_or_default(obj, "softening", json::value_to_f64)?,
+            tags: json::map_obj_item_or_default(obj, "tags", |value| {
+                json::value_to_vec(value, json::value_to_string)
+            })?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_force() {
+        let law = InteractionLaw::Gravitation { constant: 2. };
+        assert_eq!(law.get_force(2., 3., 4.), -6.);
+        let law = InteractionLaw::InverseSquare { strength: 8. };
+        assert_eq!(law.get_force(2., 3., 4.), 2.);
+        let law = InteractionLaw::LennardJones {
+            depth: 1.5,
+            radius: 2.,
+        };
+        assert_abs_diff_eq!(
+            law.get_force(2_f64.powf(7. / 6.), 1., 1.),
+            0.,
+            epsilon = 1e-12
+        );
+        assert!(law.get_force(2., 1., 1.) > 0.);
+        assert!(law.get_force(3., 1., 1.) < 0.);
+        let law = InteractionLaw::Potential {
+            points: vec![(1., 4.), (3., -2.), (4., -1.)],
+        };
+        assert_eq!(law.get_force(0.5, 1., 1.), 3.);
+        assert_eq!(law.get_force(2., 1., 1.), 3.);
+        assert_eq!(law.get_force(3.5, 1., 1.), -1.);
+        assert_eq!(law.get_force(5., 1., 1.), 0.);
+
+        let interaction = Interaction::new(InteractionLaw::InverseSquare { strength: 50. });
+        let pos1 = Vec3::new(1., 1., 1.);
+        assert_abs_diff_eq!(
+            interaction.get_force(&pos1, 1., &Vec3::new(4., 5., 1.), 1.),
+            Vec3::new(3., 4., 0.) * 50. / 125.,
+            epsilon = 1e-12
+        );
+        assert_eq!(interaction.get_force(&pos1, 1., &pos1, 1.), Vec3::zeros());
+        let interaction = interaction.set_softening(3.);
+        assert_abs_diff_eq!(
+            interaction.get_force(&pos1, 1., &Vec3::new(1., 5., 1.), 1.),
+            Vec3::new(0., 4., 0.) * 50. / 125.,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {"constant": 2, "type": "Gravitation"},
+              {"frames": ["a", "b"], "softening": 0.1, "strength": -3, "type": "InverseSquare"},
+              {"depth": 1, "radius": 0.5, "tags": ["magnet"], "type": "LennardJones"},
+              {"points": [[0, 1], [2, 0]], "type": "Potential"}
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let interactions = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Interaction::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            interactions,
+            vec![
+                Interaction::new(InteractionLaw::Gravitation { constant: 2. }),
+                Interaction::new(InteractionLaw::InverseSquare { strength: -3. })
+                    .add_frame_id("a".into())
+                    .add_frame_id("b".into())
+                    .set_softening(0.1),
+                Interaction::new(InteractionLaw::LennardJones {
+                    depth: 1.,
+                    radius: 0.5
+                })
+                .add_tag("magnet".into()),
+                Interaction::new(InteractionLaw::Potential {
+                    points: vec![(0., 1.), (2., 0.)]
+                }),
+            ]
+        );
+        let weight = Weight::new(1.);
+        let magnet = Weight::new(1.).add_tag("magnet".into());
+        assert!(interactions[1].applies_to(&"b".into(), &weight));
+        assert!(!interactions[1].applies_to(&"c".into(), &magnet));
+        assert!(interactions[0].applies_to(&"c".into(), &weight));
+        assert!(interactions[2].applies_to(&"c".into(), &magnet));
+        assert!(!interactions[2].applies_to(&"c".into(), &weight));
+    }
+
+    #[test]
+    fn test_from_json_value_errors() {
+        let json = r#"{"points": [[2, 1], [1, 0]], "type": "Potential"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Interaction::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected at least two potential points with increasing distances; got {"points":[[2,1],[1,0]],"type":"Potential"}"#
+        );
+
+        let json = r#"{"points": [[1, 0]], "type": "Potential"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(Interaction::from_json_value(&json_value).is_err());
+
+        let json = r#"{"type": "Magnetic"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Interaction::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Invalid interaction type: Magnetic"
+        );
+    }
+}
@@ -1,8 +1,17 @@
+use crate::CurveFrame;
 use crate::Error;
+use crate::FixedFrame;
 use crate::FrameBox;
+use crate::FrameId;
+use crate::FreeFrame;
+use crate::Position;
 use crate::RotationalFrame;
+use crate::Shape;
+use crate::SlidingPivotFrame;
 use crate::TrackFrame;
+use crate::Vec3;
 use crate::Weight;
+use crate::WheelFrame;
 
 use serde_json::Map;
 use serde_json::Value;
@@ -13,12 +22,57 @@ pub fn value_to_f64(value: &Value) -> Result<f64, Error> {
         .ok_or_else(|| Error(format!("Expected f64 value; got {}", value)))
 }
 
+pub fn value_to_u64(value: &Value) -> Result<u64, Error> {
+    value
+        .as_u64()
+        .ok_or_else(|| Error(format!("Expected u64 value; got {}", value)))
+}
+
 pub fn value_to_str(value: &Value) -> Result<&str, Error> {
     value
         .as_str()
         .ok_or_else(|| Error(format!("Expected string value; got {}", value)))
 }
 
+/// Parses an array by applying `func` to each of its items.
+pub fn value_to_vec<'a, F, T>(value: &'a Value, func: F) -> Result<Vec<T>, Error>
+where
+    F: FnMut(&'a Value) -> Result<T, Error>,
+{
+    value
+        .as_array()
+        .ok_or_else(|| Error(format!("Expected array; got {}", value)))?
+        .iter()
+        .map(func)
+        .collect()
+}
+
+pub fn value_to_string(value: &Value) -> Result<String, Error> {
+    value_to_str(value).map(String::from)
+}
+
+pub fn value_to_frame_id(value: &Value) -> Result<FrameId, Error> {
+    value_to_str(value).map(FrameId::from)
+}
+
+/// Parses an `[x, y]` point of a table.
+pub fn value_to_point(value: &Value) -> Result<(f64, f64), Error> {
+    let point = Position::from_json_value(value)?;
+    Ok((point.0[0], point.0[1]))
+}
+
+pub fn value_to_vec3(value: &Value) -> Result<Vec3, Error> {
+    let position = Position::from_json_value(value)?;
+    Ok(Vec3::new(position.0[0], position.0[1], 0.))
+}
+
+pub fn value_to_gravity(value: &Value) -> Result<Vec3, Error> {
+    match value {
+        Value::Array(_) => value_to_vec3(value),
+        _ => Ok(Vec3::new(0., -value_to_f64(value)?, 0.)),
+    }
+}
+
 pub fn value_to_json_obj(value: &Value) -> Result<&Map<String, Value>, Error> {
     value
         .as_object()
@@ -53,28 +107,27 @@ where
 pub fn value_to_frame(value: &Value) -> Result<FrameBox, Error> {
     // TODO: do more of the common frame parsing here (weights, etc.) instead
     // of repeating it in each Frame implementation.
-    let type_name = map_value_item(value, &"type", value_to_str)?;
+    let type_name = map_value_item(value, "type", value_to_str)?;
     Ok(match type_name {
+        "CurveFrame" => Box::new(CurveFrame::from_json_value(value)?),
+        "FixedFrame" => Box::new(FixedFrame::from_json_value(value)?),
+        "FreeFrame" => Box::new(FreeFrame::from_json_value(value)?),
         "RotationalFrame" => Box::new(RotationalFrame::from_json_value(value)?),
+        "SlidingPivotFrame" => Box::new(SlidingPivotFrame::from_json_value(value)?),
         "TrackFrame" => Box::new(TrackFrame::from_json_value(value)?),
+        "WheelFrame" => Box::new(WheelFrame::from_json_value(value)?),
         _ => return Err(Error(format!("Invalid frame type: {}", type_name))),
     })
 }
 
-pub fn value_to_frames(value: &Value) -> Result<Vec<FrameBox>, Error> {
-    Ok(value
-        .as_array()
-        .ok_or_else(|| Error(format!("Expected `frames` to be an array; got {}", value)))?
-        .iter()
-        .map(value_to_frame)
-        .collect::<Result<_, _>>()?)
-}
-
-pub fn value_to_weights(value: &Value) -> Result<Vec<Weight>, Error> {
-    Ok(value
-        .as_array()
-        .ok_or_else(|| Error(format!("Expected `weights` to be an array; got {}", value)))?
-        .iter()
-        .map(Weight::from_json_value)
-        .collect::<Result<_, _>>()?)
+pub fn obj_to_frame_weights(obj: &Map<String, Value>) -> Result<Vec<Weight>, Error> {
+    let mut weights = map_obj_item_or_default(obj, "weights", |value| {
+        value_to_vec(value, Weight::from_json_value)
+    })?;
+    weights.extend(map_obj_item_or_default(obj, "shapes", |value| {
+        value_to_vec(value, |value| {
+            Shape::from_json_value(value).map(|shape| shape.to_weight())
+        })
+    })?);
+    Ok(weights)
 }
@@ -8,23 +8,80 @@ use wasm_bindgen::prelude::*;
 #[cfg(not(test))]
 use web_sys::console;
 
+pub use crate::actuator::Actuator;
+pub use crate::backlash::Backlash;
+pub use crate::collisions::Collisions;
+pub use crate::coupling::Coupling;
+pub use crate::curve::Curve;
+pub use crate::curve_frame::CurveFrame;
+pub use crate::damping::Damping;
+pub use crate::deadband::Deadband;
+pub use crate::field::Field;
+pub use crate::fixed_frame::FixedFrame;
+pub use crate::fluid::Fluid;
+pub use crate::force_schedule::ForceProfile;
+pub use crate::force_schedule::ForceSchedule;
 pub use crate::frame::Frame;
 pub use crate::frame::FrameBox;
 pub use crate::frame::FrameId;
+pub use crate::free_frame::FreeFrame;
+pub use crate::interaction::Interaction;
+pub use crate::interaction::InteractionLaw;
+pub use crate::mass_schedule::MassSchedule;
+pub use crate::obstacle::Obstacle;
+pub use crate::obstacle::ObstacleShape;
+pub use crate::point_force::PointForce;
+pub use crate::prescribed_motion::MotionProfile;
+pub use crate::prescribed_motion::PrescribedMotion;
+pub use crate::random_force::RandomForce;
+pub use crate::rope::Rope;
+pub use crate::rope::RopePoint;
 pub use crate::rotational_frame::RotationalFrame;
 pub use crate::scene::Scene;
+pub use crate::shape::Shape;
+pub use crate::sliding_pivot_frame::SlidingPivotFrame;
 pub use crate::solver::Solver;
+pub use crate::spring_network::Attachment;
+pub use crate::spring_network::SpringNetwork;
 pub use crate::track_frame::TrackFrame;
 pub use crate::weight::Weight;
-
+pub use crate::wheel_frame::WheelFrame;
+pub use crate::wind::Wind;
+
+mod actuator;
+mod backlash;
+mod collisions;
+mod coupling;
+mod curve;
+mod curve_frame;
+mod damping;
+mod deadband;
+mod field;
+mod fixed_frame;
+mod fluid;
+mod force_schedule;
 mod frame;
+mod free_frame;
+mod interaction;
 mod json;
+mod mass_schedule;
+mod obstacle;
+mod point_force;
+mod prescribed_motion;
+mod random;
+mod random_force;
+mod rope;
 mod rotational_frame;
 mod scene;
+mod shape;
+mod sliding_pivot_frame;
 mod solver;
+mod spring_network;
 mod track_frame;
 mod utils;
 mod weight;
+mod wheel_frame;
+mod wind;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Error(String);
@@ -77,7 +134,7 @@ pub struct State {
 
 #[cfg(not(test))]
 pub fn log(s: &str) {
-    console::log_1(&s.clone().into());
+    console::log_1(&s.into());
 }
 
 #[cfg(test)]
@@ -89,6 +146,7 @@ pub fn log(s: &str) {
 #[derive(Debug)]
 pub struct SolverContext {
     solver: Box<Solver>,
+    time: f64,
 }
 
 fn unflatten_states(flattened_states: &[f64]) -> Vec<State> {
@@ -108,6 +166,15 @@ fn reflatten_states(flattened_states: &mut [f64], new_states: &[State]) {
     }
 }
 
+fn slice_to_position(values: &[f64]) -> Result<Position, Error> {
+    Ok(Position(values.try_into().map_err(|_| {
+        Error(format!(
+            "Expected position array with length 2; got {:?}",
+            values
+        ))
+    })?))
+}
+
 #[wasm_bindgen]
 impl SolverContext {
     fn _new(scene_json: &str) -> Result<SolverContext, Box<dyn std::error::Error>> {
@@ -115,10 +182,10 @@ impl SolverContext {
         let scene = Scene::from_json_value(&json_value)?;
         let solver = Box::new(Solver::new(scene));
         log(&format!("[rs] Solver: {:?}", solver));
-        Ok(SolverContext { solver })
+        Ok(SolverContext { solver, time: 0. })
     }
 
-    #[wasm_bindgen(catch, constructor)]
+    #[wasm_bindgen(constructor)]
     pub fn new(scene_json: &str) -> Result<SolverContext, JsValue> {
         utils::set_panic_hook();
         log(&format!(
@@ -129,19 +196,130 @@ impl SolverContext {
     }
 
     pub fn tick(
-        &self,
+        &mut self,
         flattened_states: &mut [f64],
         delta_time: f64,
         tick_count: usize,
         ext_forces: &[f64],
-    ) -> () {
+    ) {
         let mut states = unflatten_states(flattened_states);
         for _ in 0..tick_count {
-            self.solver.tick_mut(&mut states, &ext_forces, delta_time);
+            self.solver
+                .tick_mut(&mut states, ext_forces, self.time, delta_time);
+            self.time += delta_time;
         }
         reflatten_states(flattened_states, &states);
     }
 
+    #[wasm_bindgen(js_name = getDriveForces)]
+    pub fn get_drive_forces(&self, flattened_states: &[f64], ext_forces: &[f64]) -> Vec<f64> {
+        let states = unflatten_states(flattened_states);
+        self.solver.get_drive_forces(&states, ext_forces, self.time)
+    }
+
+    /// Returns the generalized forces (to be added to `ext_forces`) equivalent to a world-space
+    /// force applied at a position in the given frame's coordinate system.
+    #[wasm_bindgen(js_name = getPointForces)]
+    pub fn get_point_forces(
+        &self,
+        flattened_states: &[f64],
+        frame_id: &str,
+        position: &[f64],
+        force: &[f64],
+    ) -> Result<Vec<f64>, JsValue> {
+        let states = unflatten_states(flattened_states);
+        slice_to_position(position)
+            .and_then(|position| {
+                let force = slice_to_position(force)?;
+                let force = Vec3::new(force.0[0], force.0[1], 0.);
+                let point_force = PointForce::new(frame_id.into(), force).set_position(position);
+                self.solver.get_point_forces(&states, &[point_force])
+            })
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Applies generalized impulses (one per coordinate) to the flattened states' velocities.
+    #[wasm_bindgen(js_name = applyImpulses)]
+    pub fn apply_impulses(&self, flattened_states: &mut [f64], impulses: &[f64]) {
+        let mut states = unflatten_states(flattened_states);
+        self.solver
+            .apply_impulses_mut(&mut states, impulses, self.time);
+        reflatten_states(flattened_states, &states);
+    }
+
+    /// Applies a world-space impulse at a position in the given frame's coordinate system to the
+    /// flattened states' velocities.
+    #[wasm_bindgen(js_name = applyPointImpulse)]
+    pub fn apply_point_impulse(
+        &self,
+        flattened_states: &mut [f64],
+        frame_id: &str,
+        position: &[f64],
+        impulse: &[f64],
+    ) -> Result<(), JsValue> {
+        let mut states = unflatten_states(flattened_states);
+        slice_to_position(position)
+            .and_then(|position| {
+                let impulse = slice_to_position(impulse)?;
+                let impulse = Vec3::new(impulse.0[0], impulse.0[1], 0.);
+                let point_impulse =
+                    PointForce::new(frame_id.into(), impulse).set_position(position);
+                self.solver
+                    .apply_point_impulses_mut(&mut states, &[point_impulse], self.time)
+            })
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        reflatten_states(flattened_states, &states);
+        Ok(())
+    }
+
+    /// Converts a world-space position into the given frame's coordinate system, e.g. to find
+    /// where the mouse grabbed a body.
+    #[wasm_bindgen(js_name = getLocalPosition)]
+    pub fn get_local_position(
+        &self,
+        flattened_states: &[f64],
+        frame_id: &str,
+        position: &[f64],
+    ) -> Result<Vec<f64>, JsValue> {
+        let states = unflatten_states(flattened_states);
+        slice_to_position(position)
+            .and_then(|position| {
+                self.solver
+                    .get_local_position(&states, &frame_id.into(), &position)
+            })
+            .map(|position| position.0.to_vec())
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Returns the world-space positions of the spring networks' particles, flattened as
+    /// `[x0, y0, x1, y1, ...]`.
+    #[wasm_bindgen(js_name = getParticlePositions)]
+    pub fn get_particle_positions(&self, flattened_states: &[f64]) -> Vec<f64> {
+        let states = unflatten_states(flattened_states);
+        self.solver
+            .get_particle_positions(&states)
+            .iter()
+            .flat_map(|position| position.0.to_vec())
+            .collect()
+    }
+
+    /// Number of `(q, qd)` pairs in the flattened states, which is one per frame coordinate
+    /// rather than one per frame, followed by two per spring network particle.
+    #[wasm_bindgen(getter, js_name = coordinateCount)]
+    pub fn coordinate_count(&self) -> usize {
+        self.solver.get_coordinate_count()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_time(&mut self, time: f64) {
+        self.time = time;
+    }
+
     #[wasm_bindgen(js_name = setRungeKutta)]
     pub fn set_runge_kutta(&mut self, runge_kutta: bool) {
         log(&format!("[rs] setting runge_kutta={}", runge_kutta));
@@ -149,7 +327,7 @@ impl SolverContext {
     }
 
     pub fn dispose(self) {
-        log(&"[rs] Dropping solver context");
+        log("[rs] Dropping solver context");
     }
 }
 
@@ -182,7 +360,7 @@ mod tests {
 
         #[test]
         fn ok() {
-            let value = serde_json::from_str(&"[12.0, 34.5]").unwrap();
+            let value = serde_json::from_str("[12.0, 34.5]").unwrap();
             assert_eq!(
                 Position::from_json_value(&value).unwrap(),
                 Position([12., 34.5])
@@ -191,7 +369,7 @@ mod tests {
 
         #[test]
         fn int_values() {
-            let value = serde_json::from_str(&"[12, 34]").unwrap();
+            let value = serde_json::from_str("[12, 34]").unwrap();
             assert_eq!(
                 Position::from_json_value(&value).unwrap(),
                 Position([12., 34.])
@@ -200,7 +378,7 @@ mod tests {
 
         #[test]
         fn bool_values() {
-            let value = serde_json::from_str(&"[true, false]").unwrap();
+            let value = serde_json::from_str("[true, false]").unwrap();
             assert_eq!(
                 Position::from_json_value(&value).unwrap_err().to_string(),
                 "Expected f64 value; got true"
@@ -209,7 +387,7 @@ mod tests {
 
         #[test]
         fn non_array_type() {
-            let value = serde_json::from_str(&"{}").unwrap();
+            let value = serde_json::from_str("{}").unwrap();
             assert_eq!(
                 Position::from_json_value(&value).unwrap_err().to_string(),
                 "Expected position array; got {}"
@@ -218,7 +396,7 @@ mod tests {
 
         #[test]
         fn wrong_length() {
-            let value = serde_json::from_str(&"[1.0, 2.0, 3.0]").unwrap();
+            let value = serde_json::from_str("[1.0, 2.0, 3.0]").unwrap();
             assert_eq!(
                 Position::from_json_value(&value).unwrap_err().to_string(),
                 "Expected position array with length 2; got [1.0,2.0,3.0]"
@@ -227,7 +405,7 @@ mod tests {
 
         #[test]
         fn null_value() {
-            let value = serde_json::from_str(&"[1.0, null]").unwrap();
+            let value = serde_json::from_str("[1.0, null]").unwrap();
             assert_eq!(
                 Position::from_json_value(&value).unwrap_err().to_string(),
                 "Expected f64 value; got null"
@@ -241,4 +419,43 @@ mod tests {
         assert_eq!(context.solver.scene.frames.len(), 1);
         //assert_eq!(1, 0);
     }
+
+    #[test]
+    fn test_solver_context_tick() {
+        let mut context = SolverContext::_new(TEST_SCENE_JSON).unwrap();
+        let mut flattened_states = [0., 2.];
+        context.tick(&mut flattened_states, 0.25, 4, &[0.]);
+        assert_abs_diff_eq!(context.time(), 1.);
+        assert!(flattened_states[0] > 1.);
+        assert!(flattened_states[1] < 2.);
+
+        context.set_time(0.);
+        assert_eq!(context.time(), 0.);
+    }
+
+    #[test]
+    fn test_solver_context_point_forces() {
+        let context = SolverContext::_new(TEST_SCENE_JSON).unwrap();
+        let flattened_states = [2., 0.];
+        let local_position = context
+            .get_local_position(&flattened_states, "cart", &[15., 35.5])
+            .unwrap();
+        assert_eq!(local_position, vec![1., 1.]);
+        let forces = context
+            .get_point_forces(&flattened_states, "cart", &local_position, &[3., 4.])
+            .unwrap();
+        assert_eq!(forces, vec![3.]);
+    }
+
+    #[test]
+    fn test_solver_context_impulses() {
+        let context = SolverContext::_new(TEST_SCENE_JSON).unwrap();
+        let mut flattened_states = [2., 1.];
+        context.apply_impulses(&mut flattened_states, &[500.]);
+        assert_eq!(flattened_states, [2., 3.]);
+        context
+            .apply_point_impulse(&mut flattened_states, "cart", &[0., 0.], &[-250., 7.])
+            .unwrap();
+        assert_eq!(flattened_states, [2., 2.]);
+    }
 }
@@ -0,0 +1,82 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+use crate::MotionProfile;
+
+/// Makes the mass of one of a frame's weights (by index) follow a function of simulation time,
+/// e.g. for a draining bucket.  Mass comes and goes at the weight's own velocity, so changing it
+/// doesn't push the weight around by itself; a rocket's thrust has to be applied as a force.  The
+/// weight's inertia stays as it is, and the mass never drops below zero.
+///
+/// Schedules can also be swapped out between ticks through the solver's scene, e.g. to set a new
+/// constant mass.
+#[derive(Debug, PartialEq)]
+pub struct MassSchedule {
+    pub frame_id: FrameId,
+    pub profile: MotionProfile,
+    pub weight_index: usize,
+}
+
+impl MassSchedule {
+    pub fn new(frame_id: FrameId, weight_index: usize, profile: MotionProfile) -> Self {
+        Self {
+            frame_id,
+            profile,
+            weight_index,
+        }
+    }
+
+    pub fn get_mass(&self, time: f64) -> f64 {
+        self.profile.get_state(time).q.max(0.)
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(MassSchedule {
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            profile: MotionProfile::from_json_value(value)?,
+            weight_index: json::map_obj_item_or_default(obj, "weight", json::value_to_u64)?
+                as usize,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_mass() {
+        let schedule = MassSchedule::new(
+            "a".into(),
+            0,
+            MotionProfile::Polynomial {
+                coefficients: vec![5., -2.],
+            },
+        );
+        assert_eq!(schedule.get_mass(0.), 5.);
+        assert_eq!(schedule.get_mass(2.), 1.);
+        assert_eq!(schedule.get_mass(3.), 0.);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json =
+            r#"{"coefficients": [5, -0.5], "frame": "a", "type": "Polynomial", "weight": 1}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            MassSchedule::from_json_value(&json_value).unwrap(),
+            MassSchedule::new(
+                "a".into(),
+                1,
+                MotionProfile::Polynomial {
+                    coefficients: vec![5., -0.5]
+                }
+            )
+        );
+
+        let json = r#"{"frame": "a", "type": "Polynomial", "weight": -1}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(MassSchedule::from_json_value(&json_value).is_err());
+    }
+}
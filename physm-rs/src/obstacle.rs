@@ -0,0 +1,210 @@
+use crate::json;
+use crate::Error;
+use crate::Position;
+use crate::Vec3;
+
+/// Static geometry fixed in the world, which weights collide with.
+#[derive(Debug, PartialEq)]
+pub enum ObstacleShape {
+    /// Solid disc, e.g. a peg.
+    Circle { center: Position, radius: f64 },
+    /// Everything on the far side of the line through `position`, with `normal` pointing out of
+    /// the solid side (e.g. `[0, 1]` for a floor).
+    HalfPlane {
+        normal: Position,
+        position: Position,
+    },
+    /// Thin wall between two points, which weights bounce off of from either side.  Weights with
+    /// no radius pass straight through segments, so give them a radius to collide.
+    Segment {
+        end_pos: Position,
+        start_pos: Position,
+    },
+}
+
+impl ObstacleShape {
+    /// Returns the contact normal (pointing from the obstacle towards the weight) and the depth
+    /// of penetration of a weight with the given radius at `pos`, if they overlap.
+    pub fn get_contact(&self, pos: &Vec3, radius: f64) -> Option<(Vec3, f64)> {
+        let (offset, distance) = match self {
+            ObstacleShape::Circle {
+                center,
+                radius: circle_radius,
+            } => {
+                let offset = pos - center.to_vec3();
+                let distance = offset.norm();
+                (offset, distance - circle_radius)
+            }
+            ObstacleShape::HalfPlane { normal, position } => {
+                let normal = Vec3::new(normal.0[0], normal.0[1], 0.).normalize();
+                (normal, (pos - position.to_vec3()).dot(&normal))
+            }
+            ObstacleShape::Segment { end_pos, start_pos } => {
+                let direction = end_pos.to_vec3() - start_pos.to_vec3();
+                let fraction = match direction.norm_squared() > 0. {
+                    true => ((pos - start_pos.to_vec3()).dot(&direction)
+                        / direction.norm_squared())
+                    .clamp(0., 1.),
+                    false => 0.,
+                };
+                let offset = pos - (start_pos.to_vec3() + direction * fraction);
+                let distance = offset.norm();
+                (offset, distance)
+            }
+        };
+        match distance < radius && offset.norm() > 0. {
+            true => Some((offset.normalize(), radius - distance)),
+            false => None,
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "Circle" => ObstacleShape::Circle {
+                center: json::map_value_item(value, "center", Position::from_json_value)?,
+                radius: json::map_value_item(value, "radius", json::value_to_f64)?,
+            },
+            "HalfPlane" => ObstacleShape::HalfPlane {
+                normal: json::map_value_item(value, "normal", Position::from_json_value)?,
+                position: json::map_value_item(value, "position", Position::from_json_value)?,
+            },
+            "Segment" => ObstacleShape::Segment {
+                end_pos: json::map_value_item(value, "endPos", Position::from_json_value)?,
+                start_pos: json::map_value_item(value, "startPos", Position::from_json_value)?,
+            },
+            _ => return Err(Error(format!("Invalid obstacle type: {}", type_name))),
+        })
+    }
+}
+
+/// Piece of static world geometry (floor, walls, pegs, etc.) that weights collide with according
+/// to their `radius`.  Collisions lose a share of the approaching speed depending on
+/// `restitution` (from 0 for no bounce to 1 for a perfectly elastic one), and Coulomb `friction`
+/// resists sliding along the obstacle while in contact.
+#[derive(Debug, PartialEq)]
+pub struct Obstacle {
+    pub friction: f64,
+    pub restitution: f64,
+    pub shape: ObstacleShape,
+}
+
+impl Obstacle {
+    pub fn new(shape: ObstacleShape) -> Self {
+        Self {
+            friction: 0.,
+            restitution: 0.,
+            shape,
+        }
+    }
+
+    pub fn set_friction(mut self, friction: f64) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    pub fn set_restitution(mut self, restitution: f64) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Obstacle {
+            friction: json::map_obj_item_or_default(obj, "friction", json::value_to_f64)?,
+            restitution: json::map_obj_item_or_default(obj, "restitution", json::value_to_f64)?,
+            shape: ObstacleShape::from_json_value(value)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_contact() {
+        let floor = ObstacleShape::HalfPlane {
+            normal: Position([0., 2.]),
+            position: Position([0., -1.]),
+        };
+        assert_eq!(
+            floor.get_contact(&Vec3::new(3., -0.5, 1.), 1.),
+            Some((Vec3::new(0., 1., 0.), 0.5))
+        );
+        assert_eq!(
+            floor.get_contact(&Vec3::new(3., -1.5, 1.), 0.),
+            Some((Vec3::new(0., 1., 0.), 0.5))
+        );
+        assert_eq!(floor.get_contact(&Vec3::new(3., 0.5, 1.), 1.), None);
+
+        let peg = ObstacleShape::Circle {
+            center: Position([1., 1.]),
+            radius: 2.,
+        };
+        assert_eq!(
+            peg.get_contact(&Vec3::new(4., 5., 1.), 4.),
+            Some((Vec3::new(0.6, 0.8, 0.), 1.))
+        );
+        assert_eq!(peg.get_contact(&Vec3::new(4., 5., 1.), 2.), None);
+
+        let wall = ObstacleShape::Segment {
+            end_pos: Position([2., 0.]),
+            start_pos: Position([0., 0.]),
+        };
+        assert_eq!(
+            wall.get_contact(&Vec3::new(1., -0.25, 1.), 0.5),
+            Some((Vec3::new(0., -1., 0.), 0.25))
+        );
+        let (normal, depth) = wall.get_contact(&Vec3::new(2.3, 0.4, 1.), 1.).unwrap();
+        assert_abs_diff_eq!(normal, Vec3::new(0.6, 0.8, 0.), epsilon = 1e-12);
+        assert_abs_diff_eq!(depth, 0.5, epsilon = 1e-12);
+        assert_eq!(wall.get_contact(&Vec3::new(1., 0., 1.), 0.), None);
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {"normal": [0, 1], "position": [0, -5], "restitution": 0.5, "type": "HalfPlane"},
+              {"center": [1, 2], "friction": 0.3, "radius": 0.5, "type": "Circle"},
+              {"endPos": [1, 0], "startPos": [-1, 0], "type": "Segment"}
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let obstacles = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Obstacle::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            obstacles,
+            vec![
+                Obstacle::new(ObstacleShape::HalfPlane {
+                    normal: Position([0., 1.]),
+                    position: Position([0., -5.]),
+                })
+                .set_restitution(0.5),
+                Obstacle::new(ObstacleShape::Circle {
+                    center: Position([1., 2.]),
+                    radius: 0.5,
+                })
+                .set_friction(0.3),
+                Obstacle::new(ObstacleShape::Segment {
+                    end_pos: Position([1., 0.]),
+                    start_pos: Position([-1., 0.]),
+                }),
+            ]
+        );
+
+        let json = r#"{"type": "Box"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Obstacle::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Invalid obstacle type: Box"
+        );
+    }
+}
@@ -0,0 +1,27 @@
+use crate::FrameId;
+use crate::Position;
+use crate::Vec3;
+
+/// World-space force applied at a point attached to a frame (e.g. at one of its weights, or
+/// wherever the mouse grabbed it), given in the frame's own coordinate system.
+#[derive(Debug, PartialEq)]
+pub struct PointForce {
+    pub force: Vec3,
+    pub frame_id: FrameId,
+    pub position: Position,
+}
+
+impl PointForce {
+    pub fn new(frame_id: FrameId, force: Vec3) -> Self {
+        Self {
+            force,
+            frame_id,
+            position: Position([0., 0.]),
+        }
+    }
+
+    pub fn set_position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+}
@@ -0,0 +1,216 @@
+use std::f64::consts::PI;
+
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+use crate::State;
+
+#[derive(Debug, PartialEq)]
+pub enum MotionProfile {
+    /// `q = c0 + c1 * t + c2 * t^2 + ...`, which covers holding still, moving at a constant
+    /// velocity or accelerating uniformly.
+    Polynomial { coefficients: Vec<f64> },
+    /// `q = offset + amplitude * sin(2 * pi * frequency * t + phase)`.
+    Sinusoid {
+        amplitude: f64,
+        frequency: f64,
+        offset: f64,
+        phase: f64,
+    },
+}
+
+impl MotionProfile {
+    pub fn get_state(&self, time: f64) -> State {
+        match self {
+            MotionProfile::Polynomial { coefficients } => State {
+                q: get_polynomial_value(coefficients, time),
+                qd: get_polynomial_value(&get_polynomial_derivative(coefficients), time),
+            },
+            MotionProfile::Sinusoid {
+                amplitude,
+                frequency,
+                offset,
+                phase,
+            } => {
+                let omega = 2. * PI * frequency;
+                let angle = omega * time + phase;
+                State {
+                    q: offset + amplitude * angle.sin(),
+                    qd: amplitude * omega * angle.cos(),
+                }
+            }
+        }
+    }
+
+    pub fn get_accel(&self, time: f64) -> f64 {
+        match self {
+            MotionProfile::Polynomial { coefficients } => {
+                let derivative = get_polynomial_derivative(coefficients);
+                get_polynomial_value(&get_polynomial_derivative(&derivative), time)
+            }
+            MotionProfile::Sinusoid {
+                amplitude,
+                frequency,
+                phase,
+                ..
+            } => {
+                let omega = 2. * PI * frequency;
+                -amplitude * omega * omega * (omega * time + phase).sin()
+            }
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        Ok(match type_name {
+            "Polynomial" => MotionProfile::Polynomial {
+                coefficients: json::map_value_item(value, "coefficients", |value| {
+                    json::value_to_vec(value, json::value_to_f64)
+                })?,
+            },
+            "Sinusoid" => MotionProfile::Sinusoid {
+                amplitude: json::map_value_item(value, "amplitude", json::value_to_f64)?,
+                frequency: json::map_value_item(value, "frequency", json::value_to_f64)?,
+                offset: json::map_obj_item_or_default(obj, "offset", json::value_to_f64)?,
+                phase: json::map_obj_item_or_default(obj, "phase", json::value_to_f64)?,
+            },
+            _ => return Err(Error(format!("Invalid motion profile type: {}", type_name))),
+        })
+    }
+}
+
+fn get_polynomial_value(coefficients: &[f64], x: f64) -> f64 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0., |value, coefficient| value * x + coefficient)
+}
+
+fn get_polynomial_derivative(coefficients: &[f64]) -> Vec<f64> {
+    coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(power, coefficient)| power as f64 * coefficient)
+        .collect()
+}
+
+/// Kinematic driver that makes a frame's coordinate follow a function of simulation time rather
+/// than being solved for.  The frame still moves its descendants, and the solver reports the
+/// generalized force needed to drive it.
+#[derive(Debug, PartialEq)]
+pub struct PrescribedMotion {
+    pub frame_id: FrameId,
+    pub profile: MotionProfile,
+}
+
+impl PrescribedMotion {
+    pub fn new(frame_id: FrameId, profile: MotionProfile) -> Self {
+        Self { frame_id, profile }
+    }
+
+    pub fn get_state(&self, time: f64) -> State {
+        self.profile.get_state(time)
+    }
+
+    pub fn get_accel(&self, time: f64) -> f64 {
+        self.profile.get_accel(time)
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        Ok(PrescribedMotion {
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            profile: MotionProfile::from_json_value(value)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polynomial() {
+        let profile = MotionProfile::Polynomial {
+            coefficients: vec![1., 2., 3.],
+        };
+        let state = profile.get_state(2.);
+        assert_eq!(state.q, 1. + 4. + 12.);
+        assert_eq!(state.qd, 2. + 12.);
+        assert_eq!(profile.get_accel(2.), 6.);
+
+        let profile = MotionProfile::Polynomial {
+            coefficients: vec![],
+        };
+        assert_eq!(profile.get_state(2.).q, 0.);
+        assert_eq!(profile.get_accel(2.), 0.);
+    }
+
+    #[test]
+    fn test_sinusoid() {
+        let profile = MotionProfile::Sinusoid {
+            amplitude: 2.,
+            frequency: 0.25,
+            offset: 1.,
+            phase: 0.,
+        };
+        let state = profile.get_state(0.);
+        assert_abs_diff_eq!(state.q, 1.);
+        assert_abs_diff_eq!(state.qd, 2. * PI / 2.);
+        assert_abs_diff_eq!(profile.get_accel(0.), 0.);
+        let state = profile.get_state(1.);
+        assert_abs_diff_eq!(state.q, 3.);
+        assert_abs_diff_eq!(state.qd, 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(profile.get_accel(1.), -2. * (PI / 2.) * (PI / 2.));
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {"coefficients": [0, 1.5], "frame": "a", "type": "Polynomial"},
+              {"amplitude": 0.1, "frame": "b", "frequency": 20, "type": "Sinusoid"}
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let motions = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(PrescribedMotion::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            motions,
+            vec![
+                PrescribedMotion::new(
+                    "a".into(),
+                    MotionProfile::Polynomial {
+                        coefficients: vec![0., 1.5]
+                    }
+                ),
+                PrescribedMotion::new(
+                    "b".into(),
+                    MotionProfile::Sinusoid {
+                        amplitude: 0.1,
+                        frequency: 20.,
+                        offset: 0.,
+                        phase: 0.
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_invalid_type() {
+        let json = r#"{"frame": "a", "type": "Spline"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            PrescribedMotion::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Invalid motion profile type: Spline"
+        );
+    }
+}
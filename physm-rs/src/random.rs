@@ -0,0 +1,46 @@
+use std::f64::consts::PI;
+
+// Mixes the bits of a value thoroughly, as in the SplitMix64 generator.
+fn mix(value: u64) -> u64 {
+    let value = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+// Maps a hash onto a uniform random number in the open interval `(0, 1)`.
+fn to_uniform(hash: u64) -> f64 {
+    ((hash >> 11) as f64 + 0.5) / (1_u64 << 53) as f64
+}
+
+/// Returns a standard normal random number that's determined entirely by the given keys (e.g. a
+/// seed, the time, and a coordinate index).  Hashing the keys rather than advancing a generator
+/// means that nothing has to carry the generator's state between ticks, while replaying the
+/// same keys reproduces a run exactly.
+pub fn get_normal(keys: &[u64]) -> f64 {
+    let hash = keys.iter().fold(0, |hash, key| mix(hash ^ key));
+    let radius = (-2. * to_uniform(hash).ln()).sqrt();
+    let angle = 2. * PI * to_uniform(mix(hash));
+    radius * angle.cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_normal() {
+        assert_eq!(get_normal(&[1, 2, 3]), get_normal(&[1, 2, 3]));
+        assert_ne!(get_normal(&[1, 2, 3]), get_normal(&[1, 2, 4]));
+        assert_ne!(get_normal(&[1, 2, 3]), get_normal(&[3, 2, 1]));
+
+        let count = 100000;
+        let values = (0..count)
+            .map(|index| get_normal(&[7, index]))
+            .collect::<Vec<f64>>();
+        let mean = values.iter().sum::<f64>() / count as f64;
+        let variance = values.iter().map(|value| value * value).sum::<f64>() / count as f64;
+        assert_abs_diff_eq!(mean, 0., epsilon = 0.01);
+        assert_abs_diff_eq!(variance, 1., epsilon = 0.02);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+
+/// White-noise generalized force on each of a frame's coordinates, e.g. to model Brownian motion
+/// or to shake an inverted pendulum off balance.  The noise's `intensity` is the variance it adds
+/// to a coordinate's generalized momentum per unit time.
+///
+/// A nonzero `temperature` adds the thermal noise that goes with the frame's `resistance` by the
+/// fluctuation-dissipation relation (i.e. `2 * resistance * temperature`, in units where
+/// Boltzmann's constant is 1), so that each coordinate settles at an average kinetic energy of
+/// `temperature / 2`.
+#[derive(Debug, PartialEq)]
+pub struct RandomForce {
+    pub frame_id: FrameId,
+    pub intensity: f64,
+    pub temperature: f64,
+}
+
+impl RandomForce {
+    pub fn new(frame_id: FrameId) -> Self {
+        Self {
+            frame_id,
+            intensity: 0.,
+            temperature: 0.,
+        }
+    }
+
+    pub fn set_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    pub fn set_temperature(mut self, temperature: f64) -> Self {
+        self.temperature = temperature;
+        self
+    }
+
+    /// Returns the total noise intensity on a coordinate of the frame with the given resistance.
+    pub fn get_intensity(&self, resistance: f64) -> f64 {
+        self.intensity + 2. * resistance * self.temperature
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let random_force = RandomForce {
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            intensity: json::map_obj_item_or_default(obj, "intensity", json::value_to_f64)?,
+            temperature: json::map_obj_item_or_default(obj, "temperature", json::value_to_f64)?,
+        };
+        if random_force.intensity < 0. || random_force.temperature < 0. {
+            return Err(Error(format!(
+                "Expected random force with a non-negative intensity and temperature; got {}",
+                value
+            )));
+        }
+        Ok(random_force)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"{"frame": "a", "intensity": 0.5, "temperature": 2}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let random_force = RandomForce::from_json_value(&json_value).unwrap();
+        assert_eq!(
+            random_force,
+            RandomForce::new("a".into())
+                .set_intensity(0.5)
+                .set_temperature(2.)
+        );
+        assert_eq!(random_force.get_intensity(3.), 12.5);
+
+        let json = r#"{"temperature": 2}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(RandomForce::from_json_value(&json_value).is_err());
+
+        let json = r#"{"frame": "a", "intensity": -0.5}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            RandomForce::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected random force with a non-negative intensity and temperature; got {"frame":"a","intensity":-0.5}"#
+        );
+
+        let json = r#"{"frame": "a", "temperature": -2}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(RandomForce::from_json_value(&json_value).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+use crate::Position;
+
+/// Point that a rope is tied to or runs over, either fixed in the world or attached to a frame
+/// (at a position in the frame's own coordinate system).
+#[derive(Debug, PartialEq)]
+pub struct RopePoint {
+    pub frame_id: Option<FrameId>,
+    pub position: Position,
+}
+
+impl RopePoint {
+    pub fn new(position: Position) -> Self {
+        Self {
+            frame_id: None,
+            position,
+        }
+    }
+
+    pub fn set_frame_id(mut self, frame_id: FrameId) -> Self {
+        self.frame_id = Some(frame_id);
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(RopePoint {
+            frame_id: json::map_obj_item_or_default(obj, "frame", |value| {
+                json::value_to_str(value).map(|frame_id| Some(frame_id.into()))
+            })?,
+            position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
+        })
+    }
+}
+
+/// Inextensible rope running through a sequence of points, e.g. from one weight over fixed pulleys
+/// to another for an Atwood machine, or back and forth between pulleys attached to frames for a
+/// block-and-tackle.  The first and last points are the rope's ends; the ones in between are
+/// pulleys, which are treated as frictionless points.
+///
+/// The rope only pulls: it holds the total length between the points at `length` while taut and
+/// has no effect once it goes slack.
+#[derive(Debug, PartialEq)]
+pub struct Rope {
+    pub length: f64,
+    pub points: Vec<RopePoint>,
+}
+
+impl Rope {
+    pub fn new(length: f64) -> Self {
+        Self {
+            length,
+            points: Vec::new(),
+        }
+    }
+
+    pub fn add_point(mut self, point: RopePoint) -> Self {
+        self.points.push(point);
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let rope = Rope {
+            length: json::map_value_item(value, "length", json::value_to_f64)?,
+            points: json::map_value_item(value, "points", |value| {
+                json::value_to_vec(value, RopePoint::from_json_value)
+            })?,
+        };
+        if rope.points.len() < 2 {
+            return Err(Error(format!(
+                "Expected at least two rope points; got {}",
+                value
+            )));
+        }
+        Ok(rope)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "length": 6,
+              "points": [
+                {
+                  "frame": "a",
+                  "position": [
+                    0,
+                    0.5
+                  ]
+                },
+                {
+                  "position": [
+                    -1,
+                    0
+                  ]
+                },
+                {
+                  "frame": "b"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Rope::from_json_value(&json_value).unwrap(),
+            Rope::new(6.)
+                .add_point(RopePoint::new(Position([0., 0.5])).set_frame_id("a".into()))
+                .add_point(RopePoint::new(Position([-1., 0.])))
+                .add_point(RopePoint::new(Position([0., 0.])).set_frame_id("b".into()))
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_errors() {
+        let json = r#"{"length": 6, "points": [{"frame": "a"}]}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Rope::from_json_value(&json_value).unwrap_err().to_string(),
+            r#"Expected at least two rope points; got {"length":6,"points":[{"frame":"a"}]}"#
+        );
+
+        let json = r#"{"points": []}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(Rope::from_json_value(&json_value).is_err());
+    }
+}
@@ -20,7 +20,7 @@ impl RotationalFrame {
     pub fn new(id: FrameId) -> Self {
         Self {
             children: Vec::new(),
-            id: id,
+            id,
             position: Position([0.0, 0.0]),
             resistance: 0.,
             weights: Vec::new(),
@@ -50,11 +50,13 @@ impl RotationalFrame {
     pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
         let obj = json::value_to_json_obj(value)?;
         Ok(RotationalFrame {
-            children: json::map_obj_item_or_default(obj, "frames", json::value_to_frames)?,
-            id: json::map_value_item(value, &"id", json::value_to_str)?.into(),
+            children: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            id: json::map_value_item(value, "id", json::value_to_str)?.into(),
             position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
             resistance: json::map_obj_item_or_default(obj, "resistance", json::value_to_f64)?,
-            weights: json::map_obj_item_or_default(obj, "weights", json::value_to_weights)?,
+            weights: json::obj_to_frame_weights(obj)?,
         })
     }
 }
@@ -76,7 +78,8 @@ impl Frame for RotationalFrame {
         &self.weights
     }
 
-    fn get_local_pos_matrix(&self, q: f64) -> Mat3 {
+    fn get_local_pos_matrix(&self, qs: &[f64]) -> Mat3 {
+        let q = qs[0];
         // TODO: use nalgebra's isometry.
         Mat3::new(
             q.cos(),
@@ -91,11 +94,13 @@ impl Frame for RotationalFrame {
         )
     }
 
-    fn get_local_vel_matrix(&self, q: f64) -> Mat3 {
+    fn get_local_vel_matrix(&self, qs: &[f64], _index: usize) -> Mat3 {
+        let q = qs[0];
         Mat3::new(-q.sin(), -q.cos(), 0., q.cos(), -q.sin(), 0., 0., 0., 0.)
     }
 
-    fn get_local_accel_matrix(&self, q: f64) -> Mat3 {
+    fn get_local_accel_matrix(&self, qs: &[f64], _index1: usize, _index2: usize) -> Mat3 {
+        let q = qs[0];
         Mat3::new(-q.cos(), q.sin(), 0., -q.sin(), -q.cos(), 0., 0., 0., 0.)
     }
 }
@@ -128,7 +133,7 @@ mod tests {
         );
         assert_eq!(
             format!("{:?}", frame.weights),
-            "[Weight { mass: 12.0, position: Position([0.0, 0.0]), drag: 0.0 }]",
+            "[Weight { mass: 12.0, position: Position([0.0, 0.0]), drag: 0.0, inertia: 0.0, quadratic_drag: 0.0, radius: 0.0, volume: 0.0, tags: [] }]",
         );
     }
 
@@ -154,6 +159,16 @@ mod tests {
                 56,
                 78.9
               ],
+              "shapes": [
+                {
+                  "endPos": [
+                    6,
+                    0
+                  ],
+                  "mass": 2,
+                  "type": "Rod"
+                }
+              ],
               "type": "RotationalFrame",
               "weights": [
                 {
@@ -166,7 +181,7 @@ mod tests {
                 }
               ]
             }"#;
-        let json_value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
         let frame = RotationalFrame::from_json_value(&json_value).unwrap();
         assert_eq!(frame.id, "a");
         assert_eq!(frame.position, Position([56., 78.9]));
@@ -181,19 +196,24 @@ mod tests {
         );
         assert_eq!(
             frame.weights,
-            vec![Weight::new(55.)
-                .set_drag(27.)
-                .set_position(Position([3., -4.]))]
+            vec![
+                Weight::new(55.)
+                    .set_drag(27.)
+                    .set_position(Position([3., -4.])),
+                Weight::new(2.)
+                    .set_position(Position([3., 0.]))
+                    .set_inertia(6.),
+            ]
         );
     }
 
     #[test]
     fn test_get_local_pos_matrix() {
         let frame = RotationalFrame::new("a".into());
-        assert_abs_diff_eq!(frame.get_local_pos_matrix(0.), Mat3::identity());
+        assert_abs_diff_eq!(frame.get_local_pos_matrix(&[0.]), Mat3::identity());
         let frame = frame.set_position(Position([3., 4.]));
         assert_abs_diff_eq!(
-            frame.get_local_pos_matrix(PI / 3.),
+            frame.get_local_pos_matrix(&[PI / 3.]),
             Mat3::new(
                 0.500, -0.866, 3.000, //
                 0.866, 0.5000, 4.000, //
@@ -207,7 +227,7 @@ mod tests {
     fn test_get_local_vel_matrix() {
         let frame = RotationalFrame::new("a".into()).set_position(Position([3., 4.]));
         assert_abs_diff_eq!(
-            frame.get_local_vel_matrix(PI / 3.),
+            frame.get_local_vel_matrix(&[PI / 3.], 0),
             Mat3::new(
                 -0.866, -0.500, 0.000, //
                 0.5000, -0.866, 0.000, //
@@ -221,7 +241,7 @@ mod tests {
     fn test_get_local_accel_matrix() {
         let frame = RotationalFrame::new("a".into()).set_position(Position([3., 4.]));
         assert_abs_diff_eq!(
-            frame.get_local_accel_matrix(PI / 3.),
+            frame.get_local_accel_matrix(&[PI / 3.], 0, 0),
             Mat3::new(
                 -0.500, 0.866, 0.000, //
                 -0.866, -0.500, 0.000, //
@@ -1,7 +1,24 @@
 use crate::json;
+use crate::Actuator;
+use crate::Collisions;
+use crate::Coupling;
+use crate::Damping;
+use crate::Deadband;
 use crate::Error;
+use crate::Field;
+use crate::Fluid;
+use crate::ForceSchedule;
 use crate::FrameBox;
+use crate::FrameId;
+use crate::Interaction;
+use crate::MassSchedule;
+use crate::Obstacle;
+use crate::PrescribedMotion;
+use crate::RandomForce;
+use crate::Rope;
+use crate::SpringNetwork;
 use crate::Vec3;
+use crate::Wind;
 
 const DEFAULT_GRAVITY: &[f64] = &[0., -10.0, 0.];
 
@@ -9,6 +26,55 @@ const DEFAULT_GRAVITY: &[f64] = &[0., -10.0, 0.];
 pub struct Scene {
     pub gravity: Vec3,
     pub frames: Vec<FrameBox>,
+    pub actuators: Vec<Actuator>,
+    pub couplings: Vec<Coupling>,
+    pub wind: Option<Wind>,
+    pub fields: Vec<Field>,
+    pub force_schedules: Vec<ForceSchedule>,
+    pub prescribed_motions: Vec<PrescribedMotion>,
+    pub ropes: Vec<Rope>,
+    pub interactions: Vec<Interaction>,
+    pub obstacles: Vec<Obstacle>,
+    pub collisions: Option<Collisions>,
+    pub random_forces: Vec<RandomForce>,
+    /// Seed for the random forces, which make the same run for the same seed.
+    pub seed: u64,
+    pub mass_schedules: Vec<MassSchedule>,
+    pub damping: Vec<Damping>,
+    pub deadbands: Vec<Deadband>,
+    pub spring_networks: Vec<SpringNetwork>,
+    pub fluid: Option<Fluid>,
+}
+
+fn find_frame<'a>(frames: &'a [FrameBox], frame_id: &FrameId) -> Option<&'a FrameBox> {
+    frames
+        .iter()
+        .find_map(|frame| match frame.get_id() == frame_id {
+            true => Some(frame),
+            false => find_frame(frame.get_children(), frame_id),
+        })
+}
+
+/// Checks that a frame that ropes, interactions, etc. refer to exists, with or without
+/// coordinates.
+fn check_frame_exists(frames: &[FrameBox], frame_id: &FrameId) -> Result<(), Error> {
+    match find_frame(frames, frame_id) {
+        None => Err(Error(format!("Unknown frame id: {}", frame_id))),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Checks that a frame targeted by an actuator, coupling, etc. exists and has a coordinate to act
+/// on (namely its first one).
+fn check_frame_id(frames: &[FrameBox], frame_id: &FrameId) -> Result<(), Error> {
+    match find_frame(frames, frame_id) {
+        None => Err(Error(format!("Unknown frame id: {}", frame_id))),
+        Some(frame) if frame.get_coordinate_count() == 0 => Err(Error(format!(
+            "Expected frame with coordinates; got {}",
+            frame_id
+        ))),
+        Some(_) => Ok(()),
+    }
 }
 
 impl Scene {
@@ -16,6 +82,23 @@ impl Scene {
         Self {
             gravity: Vec3::from_column_slice(DEFAULT_GRAVITY),
             frames: Vec::new(),
+            actuators: Vec::new(),
+            couplings: Vec::new(),
+            wind: None,
+            fields: Vec::new(),
+            force_schedules: Vec::new(),
+            prescribed_motions: Vec::new(),
+            ropes: Vec::new(),
+            interactions: Vec::new(),
+            obstacles: Vec::new(),
+            collisions: None,
+            random_forces: Vec::new(),
+            seed: 0,
+            mass_schedules: Vec::new(),
+            damping: Vec::new(),
+            deadbands: Vec::new(),
+            spring_networks: Vec::new(),
+            fluid: None,
         }
     }
 
@@ -29,22 +112,258 @@ impl Scene {
         self
     }
 
+    pub fn add_actuator(mut self, actuator: Actuator) -> Self {
+        self.actuators.push(actuator);
+        self
+    }
+
+    pub fn add_coupling(mut self, coupling: Coupling) -> Self {
+        self.couplings.push(coupling);
+        self
+    }
+
+    pub fn set_wind(mut self, wind: Wind) -> Self {
+        self.wind = Some(wind);
+        self
+    }
+
+    pub fn add_field(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn add_force_schedule(mut self, force_schedule: ForceSchedule) -> Self {
+        self.force_schedules.push(force_schedule);
+        self
+    }
+
+    pub fn add_prescribed_motion(mut self, prescribed_motion: PrescribedMotion) -> Self {
+        self.prescribed_motions.push(prescribed_motion);
+        self
+    }
+
+    pub fn add_rope(mut self, rope: Rope) -> Self {
+        self.ropes.push(rope);
+        self
+    }
+
+    pub fn add_interaction(mut self, interaction: Interaction) -> Self {
+        self.interactions.push(interaction);
+        self
+    }
+
+    pub fn add_obstacle(mut self, obstacle: Obstacle) -> Self {
+        self.obstacles.push(obstacle);
+        self
+    }
+
+    pub fn set_collisions(mut self, collisions: Collisions) -> Self {
+        self.collisions = Some(collisions);
+        self
+    }
+
+    pub fn add_random_force(mut self, random_force: RandomForce) -> Self {
+        self.random_forces.push(random_force);
+        self
+    }
+
+    pub fn set_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn add_mass_schedule(mut self, mass_schedule: MassSchedule) -> Self {
+        self.mass_schedules.push(mass_schedule);
+        self
+    }
+
+    pub fn add_damping(mut self, damping: Damping) -> Self {
+        self.damping.push(damping);
+        self
+    }
+
+    pub fn add_deadband(mut self, deadband: Deadband) -> Self {
+        self.deadbands.push(deadband);
+        self
+    }
+
+    pub fn add_spring_network(mut self, spring_network: SpringNetwork) -> Self {
+        self.spring_networks.push(spring_network);
+        self
+    }
+
+    pub fn set_fluid(mut self, fluid: Fluid) -> Self {
+        self.fluid = Some(fluid);
+        self
+    }
+
     pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
         let obj = json::value_to_json_obj(value)?;
-        Ok(Scene {
-            frames: json::map_obj_item_or_default(obj, "frames", json::value_to_frames)?,
-            gravity: Vec3::new(
-                0.,
-                -1. * json::map_obj_item_or_default(obj, "gravity", json::value_to_f64)?,
-                0.,
-            ),
-        })
+        let scene = Scene {
+            frames: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            gravity: json::map_obj_item_or_default(obj, "gravity", json::value_to_gravity)?,
+            actuators: json::map_obj_item_or_default(obj, "actuators", |value| {
+                json::value_to_vec(value, Actuator::from_json_value)
+            })?,
+            couplings: json::map_obj_item_or_default(obj, "couplings", |value| {
+                json::value_to_vec(value, Coupling::from_json_value)
+            })?,
+            wind: json::map_obj_item_or_default(obj, "wind", |value| {
+                Wind::from_json_value(value).map(Some)
+            })?,
+            fields: json::map_obj_item_or_default(obj, "fields", |value| {
+                json::value_to_vec(value, Field::from_json_value)
+            })?,
+            force_schedules: json::map_obj_item_or_default(obj, "forceSchedules", |value| {
+                json::value_to_vec(value, ForceSchedule::from_json_value)
+            })?,
+            prescribed_motions: json::map_obj_item_or_default(obj, "prescribedMotions", |value| {
+                json::value_to_vec(value, PrescribedMotion::from_json_value)
+            })?,
+            ropes: json::map_obj_item_or_default(obj, "ropes", |value| {
+                json::value_to_vec(value, Rope::from_json_value)
+            })?,
+            interactions: json::map_obj_item_or_default(obj, "interactions", |value| {
+                json::value_to_vec(value, Interaction::from_json_value)
+            })?,
+            obstacles: json::map_obj_item_or_default(obj, "obstacles", |value| {
+                json::value_to_vec(value, Obstacle::from_json_value)
+            })?,
+            collisions: json::map_obj_item_or_default(obj, "collisions", |value| {
+                Collisions::from_json_value(value).map(Some)
+            })?,
+            random_forces: json::map_obj_item_or_default(obj, "randomForces", |value| {
+                json::value_to_vec(value, RandomForce::from_json_value)
+            })?,
+            seed: json::map_obj_item_or_default(obj, "seed", json::value_to_u64)?,
+            mass_schedules: json::map_obj_item_or_default(obj, "massSchedules", |value| {
+                json::value_to_vec(value, MassSchedule::from_json_value)
+            })?,
+            damping: json::map_obj_item_or_default(obj, "damping", |value| {
+                json::value_to_vec(value, Damping::from_json_value)
+            })?,
+            deadbands: json::map_obj_item_or_default(obj, "deadbands", |value| {
+                json::value_to_vec(value, Deadband::from_json_value)
+            })?,
+            spring_networks: json::map_obj_item_or_default(obj, "springNetworks", |value| {
+                json::value_to_vec(value, SpringNetwork::from_json_value)
+            })?,
+            fluid: json::map_obj_item_or_default(obj, "fluid", |value| {
+                Fluid::from_json_value(value).map(Some)
+            })?,
+        };
+        for (index, actuator) in scene.actuators.iter().enumerate() {
+            check_frame_id(&scene.frames, &actuator.frame_id)?;
+            // The external force on an actuated coordinate is its motor's command, so each frame
+            // can only have the one motor:
+            if scene.actuators[..index]
+                .iter()
+                .any(|other| other.frame_id == actuator.frame_id)
+            {
+                return Err(Error(format!(
+                    "Duplicate actuator for frame: {}",
+                    actuator.frame_id
+                )));
+            }
+        }
+        for coupling in scene.couplings.iter() {
+            check_frame_id(&scene.frames, &coupling.frame_id)?;
+            check_frame_id(&scene.frames, &coupling.driver_frame_id)?;
+        }
+        for force_schedule in scene.force_schedules.iter() {
+            check_frame_id(&scene.frames, &force_schedule.frame_id)?;
+        }
+        for prescribed_motion in scene.prescribed_motions.iter() {
+            check_frame_id(&scene.frames, &prescribed_motion.frame_id)?;
+        }
+        for random_force in scene.random_forces.iter() {
+            check_frame_id(&scene.frames, &random_force.frame_id)?;
+            // Thermal noise scales with the resistance, so it can't go negative:
+            let frame = find_frame(&scene.frames, &random_force.frame_id).unwrap();
+            if random_force.temperature > 0. && frame.get_resistance() < 0. {
+                return Err(Error(format!(
+                    "Expected frame with non-negative resistance for thermal noise; got {}",
+                    random_force.frame_id
+                )));
+            }
+        }
+        for deadband in scene.deadbands.iter() {
+            check_frame_id(&scene.frames, &deadband.frame_id)?;
+        }
+        for mass_schedule in scene.mass_schedules.iter() {
+            let frame = find_frame(&scene.frames, &mass_schedule.frame_id)
+                .ok_or_else(|| Error(format!("Unknown frame id: {}", mass_schedule.frame_id)))?;
+            if mass_schedule.weight_index >= frame.get_weights().len() {
+                return Err(Error(format!(
+                    "Invalid weight index for frame {}: {}",
+                    mass_schedule.frame_id, mass_schedule.weight_index
+                )));
+            }
+        }
+        for damping in scene.damping.iter() {
+            if let Damping::Matrix { frame_ids, matrix } = damping {
+                let mut coordinate_count = 0;
+                for frame_id in frame_ids.iter() {
+                    check_frame_id(&scene.frames, frame_id)?;
+                    coordinate_count += find_frame(&scene.frames, frame_id)
+                        .unwrap()
+                        .get_coordinate_count();
+                }
+                if matrix.nrows() != coordinate_count {
+                    return Err(Error(format!(
+                        "Expected damping matrix of size {}; got {}",
+                        coordinate_count,
+                        matrix.nrows()
+                    )));
+                }
+            }
+        }
+        // Ropes, interactions and spring networks can involve any frame, including ones without
+        // coordinates of their own:
+        for rope in scene.ropes.iter() {
+            for frame_id in rope
+                .points
+                .iter()
+                .filter_map(|point| point.frame_id.as_ref())
+            {
+                check_frame_exists(&scene.frames, frame_id)?;
+            }
+        }
+        for interaction in scene.interactions.iter() {
+            for frame_id in interaction.frame_ids.iter() {
+                check_frame_exists(&scene.frames, frame_id)?;
+            }
+        }
+        for attachment in scene
+            .spring_networks
+            .iter()
+            .flat_map(|spring_network| spring_network.attachments.iter())
+        {
+            let frame = find_frame(&scene.frames, &attachment.frame_id)
+                .ok_or_else(|| Error(format!("Unknown frame id: {}", attachment.frame_id)))?;
+            if attachment.weight_index >= frame.get_weights().len() {
+                return Err(Error(format!(
+                    "Invalid weight index for frame {}: {}",
+                    attachment.frame_id, attachment.weight_index
+                )));
+            }
+        }
+        Ok(scene)
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Actuator;
     use crate::RotationalFrame;
     use crate::TrackFrame;
 
@@ -79,7 +398,7 @@ mod tests {
               ],
               "gravity": 5.1
             }"#;
-        let json_value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
         let actual_scene = Scene::from_json_value(&json_value).unwrap();
         let expected_scene = Scene::new()
             .set_gravity(Vec3::new(0., -5.1, 0.))
@@ -95,4 +414,462 @@ mod tests {
             format!("{:?}", expected_scene),
         );
     }
+
+    #[test]
+    fn test_from_json_value_actuators() {
+        let json = r#"
+            {
+              "actuators": [
+                {
+                  "frame": "b",
+                  "noLoadSpeed": 20,
+                  "stallTorque": 1.5
+                }
+              ],
+              "frames": [
+                {
+                  "frames": [
+                    {
+                      "id": "b",
+                      "type": "RotationalFrame"
+                    }
+                  ],
+                  "id": "a",
+                  "type": "TrackFrame"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let scene = Scene::from_json_value(&json_value).unwrap();
+        assert_eq!(scene.actuators, vec![Actuator::new("b".into(), 1.5, 20.)]);
+
+        let json = r#"
+            {
+              "actuators": [
+                {"frame": "a", "noLoadSpeed": 20, "stallTorque": 1.5},
+                {"frame": "a", "noLoadSpeed": 10, "stallTorque": 3}
+              ],
+              "frames": [{"id": "a", "type": "TrackFrame"}]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Duplicate actuator for frame: a"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_unknown_frame_id() {
+        let json = r#"
+            {
+              "actuators": [
+                {
+                  "frame": "c",
+                  "noLoadSpeed": 20,
+                  "stallTorque": 1.5
+                }
+              ],
+              "frames": [
+                {
+                  "id": "a",
+                  "type": "TrackFrame"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Unknown frame id: c"
+        );
+
+        let json = r#"
+            {
+              "actuators": [
+                {
+                  "frame": "b",
+                  "noLoadSpeed": 20,
+                  "stallTorque": 1.5
+                }
+              ],
+              "frames": [
+                {
+                  "frames": [
+                    {
+                      "id": "b",
+                      "type": "FixedFrame"
+                    }
+                  ],
+                  "id": "a",
+                  "type": "TrackFrame"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Expected frame with coordinates; got b"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_gravity() {
+        let get_gravity = |json: &str| {
+            let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+            Scene::from_json_value(&json_value).map(|scene| scene.gravity)
+        };
+        assert_eq!(get_gravity(r#"{}"#).unwrap(), Vec3::zeros());
+        assert_eq!(
+            get_gravity(r#"{"gravity": 9.8}"#).unwrap(),
+            Vec3::new(0., -9.8, 0.)
+        );
+        assert_eq!(
+            get_gravity(r#"{"gravity": [3, -4]}"#).unwrap(),
+            Vec3::new(3., -4., 0.)
+        );
+        assert_eq!(
+            get_gravity(r#"{"gravity": "down"}"#)
+                .unwrap_err()
+                .to_string(),
+            r#"Expected f64 value; got "down""#
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_fields() {
+        let json = r#"
+            {
+              "fields": [
+                {
+                  "angularVelocity": 0.5,
+                  "type": "Rotating"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let scene = Scene::from_json_value(&json_value).unwrap();
+        assert_eq!(
+            scene.fields,
+            vec![Field::Rotating {
+                angular_velocity: 0.5,
+                center: Vec3::zeros()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_force_schedules() {
+        let json = r#"
+            {
+              "forceSchedules": [
+                {
+                  "frame": "a",
+                  "magnitude": 2,
+                  "time": 1,
+                  "type": "Step"
+                }
+              ],
+              "frames": [
+                {
+                  "id": "a",
+                  "type": "TrackFrame"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let scene = Scene::from_json_value(&json_value).unwrap();
+        assert_eq!(
+            scene.force_schedules,
+            vec![ForceSchedule::new(
+                "a".into(),
+                crate::ForceProfile::Step {
+                    magnitude: 2.,
+                    time: 1.
+                }
+            )]
+        );
+
+        let json = r#"
+            {
+              "forceSchedules": [
+                {
+                  "frame": "b",
+                  "magnitude": 2,
+                  "type": "Step"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Unknown frame id: b"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_prescribed_motions() {
+        let json = r#"
+            {
+              "frames": [
+                {
+                  "id": "a",
+                  "type": "TrackFrame"
+                }
+              ],
+              "prescribedMotions": [
+                {
+                  "amplitude": 0.1,
+                  "frame": "a",
+                  "frequency": 20,
+                  "type": "Sinusoid"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let scene = Scene::from_json_value(&json_value).unwrap();
+        assert_eq!(
+            scene.prescribed_motions,
+            vec![PrescribedMotion::new(
+                "a".into(),
+                crate::MotionProfile::Sinusoid {
+                    amplitude: 0.1,
+                    frequency: 20.,
+                    offset: 0.,
+                    phase: 0.
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_ropes() {
+        let json = r#"
+            {
+              "frames": [
+                {
+                  "id": "a",
+                  "type": "FixedFrame"
+                }
+              ],
+              "ropes": [
+                {
+                  "length": 2,
+                  "points": [
+                    {
+                      "frame": "a"
+                    },
+                    {
+                      "position": [
+                        0,
+                        2
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let scene = Scene::from_json_value(&json_value).unwrap();
+        assert_eq!(scene.ropes.len(), 1);
+
+        let json = r#"
+            {
+              "ropes": [
+                {
+                  "length": 2,
+                  "points": [
+                    {
+                      "frame": "b"
+                    },
+                    {}
+                  ]
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Unknown frame id: b"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_interactions() {
+        let json = r#"
+            {
+              "interactions": [
+                {
+                  "frames": [
+                    "b"
+                  ],
+                  "strength": 1,
+                  "type": "InverseSquare"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Unknown frame id: b"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_obstacles() {
+        let json = r#"
+            {
+              "obstacles": [
+                {
+                  "normal": [
+                    0,
+                    1
+                  ],
+                  "position": [
+                    0,
+                    -5
+                  ],
+                  "type": "HalfPlane"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let scene = Scene::from_json_value(&json_value).unwrap();
+        assert_eq!(scene.obstacles.len(), 1);
+        assert_eq!(scene.collisions, None);
+
+        let json = r#"{"obstacles": {}}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Expected array; got {}"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_collisions() {
+        let json = r#"{"collisions": {"restitution": 1}}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap().collisions,
+            Some(Collisions::new(1.))
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_fluid() {
+        let json = r#"{"fluid": {"density": 1000, "level": -1}}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap().fluid,
+            Some(Fluid::new(1000.).set_level(-1.))
+        );
+        let json_value: serde_json::Value = serde_json::from_str("{}").unwrap();
+        assert_eq!(Scene::from_json_value(&json_value).unwrap().fluid, None);
+    }
+
+    #[test]
+    fn test_from_json_value_random_forces() {
+        let json = r#"{"seed": 3}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(Scene::from_json_value(&json_value).unwrap().seed, 3);
+
+        let json = r#"{"seed": -1}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Expected u64 value; got -1"
+        );
+
+        let json = r#"{"randomForces": [{"frame": "b", "temperature": 1}]}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Unknown frame id: b"
+        );
+
+        let json = r#"
+            {
+              "frames": [{"id": "a", "resistance": -1, "type": "TrackFrame"}],
+              "randomForces": [{"frame": "a", "temperature": 1}]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Expected frame with non-negative resistance for thermal noise; got a"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_mass_schedules() {
+        let json = r#"
+            {
+              "frames": [
+                {
+                  "id": "a",
+                  "type": "TrackFrame",
+                  "weights": [
+                    {
+                      "drag": 0,
+                      "mass": 5,
+                      "position": [
+                        0,
+                        0
+                      ]
+                    }
+                  ]
+                }
+              ],
+              "massSchedules": [
+                {
+                  "coefficients": [
+                    5,
+                    -0.5
+                  ],
+                  "frame": "a",
+                  "type": "Polynomial",
+                  "weight": 1
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Invalid weight index for frame a: 1"
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_damping() {
+        let json = r#"
+            {
+              "damping": [
+                {
+                  "frames": [
+                    "a"
+                  ],
+                  "matrix": [
+                    [
+                      1,
+                      0
+                    ],
+                    [
+                      0,
+                      1
+                    ]
+                  ],
+                  "type": "Matrix"
+                }
+              ],
+              "frames": [
+                {
+                  "id": "a",
+                  "type": "TrackFrame"
+                }
+              ]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Scene::from_json_value(&json_value).unwrap_err().to_string(),
+            "Expected damping matrix of size 1; got 2"
+        );
+    }
 }
@@ -0,0 +1,288 @@
+use crate::json;
+use crate::Error;
+use crate::Position;
+use crate::Weight;
+
+/// Uniform mass distribution attached to a frame.  Shapes are rigid, so each one is equivalent to
+/// a single weight at its centroid, carrying the shape's moment of inertia about that centroid.
+#[derive(Debug, PartialEq)]
+pub enum Shape {
+    Disc {
+        mass: f64,
+        position: Position,
+        radius: f64,
+    },
+    Rectangle {
+        angle: f64,
+        centered: bool,
+        height: f64,
+        mass: f64,
+        position: Position,
+        width: f64,
+    },
+    Rod {
+        end_pos: Position,
+        mass: f64,
+        start_pos: Position,
+    },
+}
+
+impl Shape {
+    pub fn get_centroid(&self) -> Position {
+        match self {
+            Shape::Disc { position, .. } => Position(position.0),
+            Shape::Rectangle {
+                angle,
+                centered: false,
+                height,
+                position,
+                width,
+                ..
+            } => {
+                // Un-centered rectangles are anchored at a corner and rotated the same way as
+                // `BoxDecal`s.
+                let (half_width, half_height) = (width / 2., height / 2.);
+                Position([
+                    position.0[0] + half_width * angle.cos() + half_height * angle.sin(),
+                    position.0[1] - half_width * angle.sin() + half_height * angle.cos(),
+                ])
+            }
+            Shape::Rectangle { position, .. } => Position(position.0),
+            Shape::Rod {
+                end_pos, start_pos, ..
+            } => Position([
+                (start_pos.0[0] + end_pos.0[0]) / 2.,
+                (start_pos.0[1] + end_pos.0[1]) / 2.,
+            ]),
+        }
+    }
+
+    pub fn get_mass(&self) -> f64 {
+        match self {
+            Shape::Disc { mass, .. } | Shape::Rectangle { mass, .. } | Shape::Rod { mass, .. } => {
+                *mass
+            }
+        }
+    }
+
+    pub fn get_inertia(&self) -> f64 {
+        match self {
+            Shape::Disc { mass, radius, .. } => mass * radius * radius / 2.,
+            Shape::Rectangle {
+                height,
+                mass,
+                width,
+                ..
+            } => mass * (width * width + height * height) / 12.,
+            Shape::Rod {
+                end_pos,
+                mass,
+                start_pos,
+            } => {
+                let length_squared = (end_pos.0[0] - start_pos.0[0]).powi(2)
+                    + (end_pos.0[1] - start_pos.0[1]).powi(2);
+                mass * length_squared / 12.
+            }
+        }
+    }
+
+    pub fn get_radius(&self) -> f64 {
+        match self {
+            Shape::Disc { radius, .. } => *radius,
+            Shape::Rectangle { .. } | Shape::Rod { .. } => 0.,
+        }
+    }
+
+    pub fn to_weight(&self) -> Weight {
+        Weight::new(self.get_mass())
+            .set_position(self.get_centroid())
+            .set_inertia(self.get_inertia())
+            .set_radius(self.get_radius())
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        let mass = obj
+            .get("mass")
+            .map(json::value_to_f64)
+            .transpose()?
+            .unwrap_or(1.);
+        Ok(match type_name {
+            "Disc" => Shape::Disc {
+                mass,
+                position: json::map_obj_item_or_default(
+                    obj,
+                    "position",
+                    Position::from_json_value,
+                )?,
+                radius: json::map_value_item(value, "radius", json::value_to_f64)?,
+            },
+            "Rectangle" => Shape::Rectangle {
+                angle: json::map_obj_item_or_default(obj, "angle", json::value_to_f64)?,
+                centered: obj
+                    .get("centered")
+                    .map(|value| {
+                        value
+                            .as_bool()
+                            .ok_or_else(|| Error(format!("Expected bool value; got {}", value)))
+                    })
+                    .transpose()?
+                    .unwrap_or(true),
+                height: json::map_value_item(value, "height", json::value_to_f64)?,
+                mass,
+                position: json::map_obj_item_or_default(
+                    obj,
+                    "position",
+                    Position::from_json_value,
+                )?,
+                width: json::map_value_item(value, "width", json::value_to_f64)?,
+            },
+            "Rod" => Shape::Rod {
+                end_pos: json::map_value_item(value, "endPos", Position::from_json_value)?,
+                mass,
+                start_pos: json::map_obj_item_or_default(
+                    obj,
+                    "startPos",
+                    Position::from_json_value,
+                )?,
+            },
+            _ => return Err(Error(format!("Invalid shape type: {}", type_name))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_disc() {
+        let disc = Shape::Disc {
+            mass: 4.,
+            position: Position([1., 2.]),
+            radius: 3.,
+        };
+        assert_eq!(
+            disc.to_weight(),
+            Weight::new(4.)
+                .set_position(Position([1., 2.]))
+                .set_inertia(18.)
+                .set_radius(3.)
+        );
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let rectangle = Shape::Rectangle {
+            angle: 0.,
+            centered: true,
+            height: 2.,
+            mass: 6.,
+            position: Position([1., 2.]),
+            width: 4.,
+        };
+        assert_eq!(
+            rectangle.to_weight(),
+            Weight::new(6.)
+                .set_position(Position([1., 2.]))
+                .set_inertia(10.)
+        );
+
+        let rectangle = Shape::Rectangle {
+            angle: PI / 2.,
+            centered: false,
+            height: 2.,
+            mass: 6.,
+            position: Position([1., 2.]),
+            width: 4.,
+        };
+        let weight = rectangle.to_weight();
+        assert_abs_diff_eq!(weight.position.0[0], 2., epsilon = 1e-12);
+        assert_abs_diff_eq!(weight.position.0[1], 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(weight.inertia, 10.);
+    }
+
+    #[test]
+    fn test_rod() {
+        let rod = Shape::Rod {
+            end_pos: Position([4., 5.]),
+            mass: 3.,
+            start_pos: Position([1., 1.]),
+        };
+        assert_eq!(
+            rod.to_weight(),
+            Weight::new(3.)
+                .set_position(Position([2.5, 3.]))
+                .set_inertia(6.25)
+        );
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            [
+              {
+                "mass": 4,
+                "position": [1, 2],
+                "radius": 3,
+                "type": "Disc"
+              },
+              {
+                "centered": false,
+                "height": 2,
+                "position": [1, 2],
+                "type": "Rectangle",
+                "width": 4
+              },
+              {
+                "endPos": [4, 5],
+                "mass": 3,
+                "type": "Rod"
+              }
+            ]"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let shapes = json_value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(Shape::from_json_value)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            shapes,
+            vec![
+                Shape::Disc {
+                    mass: 4.,
+                    position: Position([1., 2.]),
+                    radius: 3.,
+                },
+                Shape::Rectangle {
+                    angle: 0.,
+                    centered: false,
+                    height: 2.,
+                    mass: 1.,
+                    position: Position([1., 2.]),
+                    width: 4.,
+                },
+                Shape::Rod {
+                    end_pos: Position([4., 5.]),
+                    mass: 3.,
+                    start_pos: Position([0., 0.]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_invalid_type() {
+        let json = r#"{"type": "Blob"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Shape::from_json_value(&json_value).unwrap_err().to_string(),
+            "Invalid shape type: Blob"
+        );
+    }
+}
@@ -1,12 +1,19 @@
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::iter;
 
+use crate::random;
+use crate::Damping;
+use crate::Error;
 use crate::FrameBox;
 use crate::FrameId;
 use crate::Mat3;
+use crate::PointForce;
+use crate::Position;
 use crate::Scene;
 use crate::State;
 use crate::Vec3;
+use crate::Weight;
 
 #[derive(Debug)]
 pub struct Solver {
@@ -14,13 +21,44 @@ pub struct Solver {
     pub runge_kutta: bool,
 }
 
+type CoordinateIndex = usize;
 type FrameIndex = usize;
 type FramePath = Vec<FrameIndex>;
 type FrameIdIndexMap<'a> = HashMap<&'a FrameId, FrameIndex>;
+type FrameIdCoordinateMap<'a> = HashMap<&'a FrameId, CoordinateIndex>;
 type FrameIndexPathMap = HashMap<FrameIndex, Vec<FrameIndex>>;
 
 type CoefficientMatrix = nalgebra::DMatrix<f64>;
 type ForceVector = nalgebra::DVector<f64>;
+type ConstraintMatrix = nalgebra::DMatrix<f64>;
+type ConstraintVector = nalgebra::DVector<f64>;
+
+// Natural frequency (in radians per unit time) of the Baumgarte stabilization that pulls drifting
+// constraints back into place; the correction is critically damped.
+const CONSTRAINT_STABILIZATION_FREQUENCY: f64 = 10.;
+
+// How much shorter than its length a rope's path can get (e.g. from the drift of a taut rope)
+// before the rope counts as slack.
+const ROPE_SLACK_TOLERANCE: f64 = 1e-6;
+
+// Approach speed below which collisions don't bounce, so that weights resting on an obstacle
+// settle instead of hopping under gravity every tick.
+const CONTACT_RESTITUTION_THRESHOLD: f64 = 0.5;
+
+// Number of passes over the contacts, which lets the impulses of simultaneous contacts (e.g. of a
+// chain lying on the floor) settle against each other.
+const CONTACT_ITERATION_COUNT: usize = 10;
+
+/// Contact of a weight with an obstacle or another weight, in terms of how fast each coordinate
+/// moves the weight along the contact normal (away from whatever it touches) and tangent.
+#[derive(Debug)]
+struct Contact {
+    depth: f64,
+    friction: f64,
+    normal_derivatives: Vec<f64>,
+    restitution: f64,
+    tangent_derivatives: Vec<f64>,
+}
 
 fn sort_frames(frames: &[FrameBox]) -> Vec<&FrameBox> {
     fn visit<'a>(frame: &'a FrameBox, sorted_frames: &mut Vec<&'a FrameBox>) {
@@ -34,7 +72,7 @@ fn sort_frames(frames: &[FrameBox]) -> Vec<&FrameBox> {
     let mut sorted_frames = Vec::new();
     frames
         .iter()
-        .for_each(|frame| visit(&frame, &mut sorted_frames));
+        .for_each(|frame| visit(frame, &mut sorted_frames));
     sorted_frames.reverse();
     sorted_frames
 }
@@ -48,6 +86,16 @@ fn get_id_index_map<'a>(frames: &'a [&FrameBox]) -> FrameIdIndexMap<'a> {
     id_index_map
 }
 
+/// Maps each frame id to the index of the frame's first coordinate.
+fn get_id_coordinate_map<'a>(frames: &'a [&FrameBox]) -> FrameIdCoordinateMap<'a> {
+    let coordinate_offsets = get_coordinate_offsets(frames);
+    frames
+        .iter()
+        .enumerate()
+        .map(|(index, frame)| (frame.get_id(), coordinate_offsets[index]))
+        .collect()
+}
+
 fn get_index_path_map(frames: &[&FrameBox]) -> FrameIndexPathMap {
     fn visit(
         frame: &FrameBox,
@@ -56,13 +104,13 @@ fn get_index_path_map(frames: &[&FrameBox]) -> FrameIndexPathMap {
         index_path_map: &mut FrameIndexPathMap,
     ) {
         let index = id_index_map[frame.get_id()];
-        if !index_path_map.contains_key(&index) {
+        if let Entry::Vacant(entry) = index_path_map.entry(index) {
             path.push(index);
-            index_path_map.insert(index, path);
+            entry.insert(path);
             frame.get_children().iter().for_each(|child| {
                 visit(
                     child,
-                    index_path_map.get(&index).unwrap().to_owned(),
+                    index_path_map[&index].to_owned(),
                     id_index_map,
                     index_path_map,
                 )
@@ -75,7 +123,7 @@ fn get_index_path_map(frames: &[&FrameBox]) -> FrameIndexPathMap {
     index_path_map.reserve(frames.len());
     frames
         .iter()
-        .for_each(|frame| visit(&frame, Vec::new(), &id_index_map, &mut index_path_map));
+        .for_each(|frame| visit(frame, Vec::new(), &id_index_map, &mut index_path_map));
     index_path_map
 }
 
@@ -90,18 +138,77 @@ fn get_parent_index(
     }
 }
 
+fn get_coordinate_offsets(frames: &[&FrameBox]) -> Vec<CoordinateIndex> {
+    iter::once(0)
+        .chain(frames.iter().map(|frame| frame.get_coordinate_count()))
+        .scan(0, |acc, x| {
+            *acc += x;
+            Some(*acc)
+        })
+        .collect()
+}
+
+fn get_coordinate_frame_indices(frames: &[&FrameBox]) -> Vec<FrameIndex> {
+    frames
+        .iter()
+        .enumerate()
+        .flat_map(|(index, frame)| iter::repeat_n(index, frame.get_coordinate_count()))
+        .collect()
+}
+
+/// The sorted frames along with where each coordinate's frame and each frame's weights sit in the
+/// flattened lists of coordinates and weights.  None of it changes as the frames move, so it's
+/// worked out once for each system of equations instead of for each of their entries.
+struct FrameLayout<'a> {
+    frames: &'a [&'a FrameBox],
+    index_path_map: &'a FrameIndexPathMap,
+    coordinate_frame_indices: Vec<FrameIndex>,
+    weight_offsets: Vec<usize>,
+}
+
+impl<'a> FrameLayout<'a> {
+    fn new(frames: &'a [&'a FrameBox], index_path_map: &'a FrameIndexPathMap) -> Self {
+        Self {
+            frames,
+            index_path_map,
+            coordinate_frame_indices: get_coordinate_frame_indices(frames),
+            weight_offsets: get_weight_offsets(frames),
+        }
+    }
+}
+
+fn get_coordinate_count(frames: &[&FrameBox]) -> usize {
+    frames
+        .iter()
+        .map(|frame| frame.get_coordinate_count())
+        .sum()
+}
+
+fn get_frame_states<'a>(
+    frame_index: FrameIndex,
+    coordinate_offsets: &[CoordinateIndex],
+    states: &'a [State],
+) -> &'a [State] {
+    &states[coordinate_offsets[frame_index]..coordinate_offsets[frame_index + 1]]
+}
+
+fn get_qs(states: &[State]) -> Vec<f64> {
+    states.iter().map(|state| state.q).collect()
+}
+
 fn get_pos_mats(
     frames: &[&FrameBox],
     index_path_map: &FrameIndexPathMap,
     states: &[State],
 ) -> Vec<Mat3> {
+    let coordinate_offsets = get_coordinate_offsets(frames);
     debug_assert_eq!(index_path_map.len(), frames.len());
-    debug_assert_eq!(states.len(), frames.len());
+    debug_assert_eq!(states.len(), *coordinate_offsets.last().unwrap());
     let get_parent_index = |index| get_parent_index(index, index_path_map);
-    let mut pos_mats = Vec::<Mat3>::new();
-    pos_mats.reserve(frames.len());
+    let mut pos_mats = Vec::<Mat3>::with_capacity(frames.len());
     frames.iter().enumerate().for_each(|(index, frame)| {
-        let local_pos_mat = frame.get_local_pos_matrix(states[index].q);
+        let qs = get_qs(get_frame_states(index, &coordinate_offsets, states));
+        let local_pos_mat = frame.get_local_pos_matrix(&qs);
         let pos_mat = match get_parent_index(index) {
             None => local_pos_mat,
             Some(parent_index) => pos_mats[parent_index] * local_pos_mat,
@@ -118,6 +225,8 @@ fn get_inv_pos_mats(pos_mats: &[Mat3]) -> Vec<Mat3> {
         .collect()
 }
 
+/// Returns a velocity matrix for each coordinate, which maps world-space positions of points
+/// attached to the coordinate's frame (or its descendents) to their velocities per unit `qd`.
 fn get_vel_mats(
     frames: &[&FrameBox],
     index_path_map: &FrameIndexPathMap,
@@ -125,27 +234,33 @@ fn get_vel_mats(
     inv_pos_mats: &[Mat3],
     states: &[State],
 ) -> Vec<Mat3> {
+    let coordinate_offsets = get_coordinate_offsets(frames);
     debug_assert_eq!(index_path_map.len(), frames.len());
     debug_assert_eq!(pos_mats.len(), frames.len());
     debug_assert_eq!(inv_pos_mats.len(), frames.len());
-    debug_assert_eq!(states.len(), frames.len());
+    debug_assert_eq!(states.len(), *coordinate_offsets.last().unwrap());
     let get_parent_index = |index| get_parent_index(index, index_path_map);
     frames
         .iter()
         .enumerate()
-        .map(|(index, frame)| {
+        .flat_map(|(index, frame)| {
+            let qs = get_qs(get_frame_states(index, &coordinate_offsets, states));
             let inv_pos_mat = &inv_pos_mats[index];
-            let local_vel_mat = frame.get_local_vel_matrix(states[index].q);
-            let rel_vel_mat = local_vel_mat * inv_pos_mat;
-            let vel_mat = match get_parent_index(index) {
-                None => rel_vel_mat,
-                Some(parent_index) => pos_mats[parent_index] * rel_vel_mat,
-            };
-            vel_mat
+            let parent_index = get_parent_index(index);
+            (0..frame.get_coordinate_count()).map(move |coordinate_index| {
+                let local_vel_mat = frame.get_local_vel_matrix(&qs, coordinate_index);
+                let rel_vel_mat = local_vel_mat * inv_pos_mat;
+                match parent_index {
+                    None => rel_vel_mat,
+                    Some(parent_index) => pos_mats[parent_index] * rel_vel_mat,
+                }
+            })
         })
         .collect()
 }
 
+/// Returns the part of each frame's acceleration matrix that comes from the velocities of its own
+/// coordinates (i.e. `sum(qd[i] * qd[j] * d2A/dq[i]dq[j])`, brought into world space).
 fn get_accel_mats(
     frames: &[&FrameBox],
     index_path_map: &FrameIndexPathMap,
@@ -153,27 +268,49 @@ fn get_accel_mats(
     inv_pos_mats: &[Mat3],
     states: &[State],
 ) -> Vec<Mat3> {
+    let coordinate_offsets = get_coordinate_offsets(frames);
     debug_assert_eq!(index_path_map.len(), frames.len());
     debug_assert_eq!(pos_mats.len(), frames.len());
     debug_assert_eq!(inv_pos_mats.len(), frames.len());
-    debug_assert_eq!(states.len(), frames.len());
+    debug_assert_eq!(states.len(), *coordinate_offsets.last().unwrap());
     let get_parent_index = |index| get_parent_index(index, index_path_map);
     frames
         .iter()
         .enumerate()
         .map(|(index, frame)| {
-            let inv_pos_mat = &inv_pos_mats[index];
-            let local_accel_mat = frame.get_local_accel_matrix(states[index].q);
-            let rel_accel_mat = local_accel_mat * inv_pos_mat;
-            let accel_mat = match get_parent_index(index) {
+            let frame_states = get_frame_states(index, &coordinate_offsets, states);
+            let qs = get_qs(frame_states);
+            let count = frame.get_coordinate_count();
+            let local_accel_mat = (0..count)
+                .flat_map(|index1| (0..count).map(move |index2| (index1, index2)))
+                .map(|(index1, index2)| {
+                    frame_states[index1].qd
+                        * frame_states[index2].qd
+                        * frame.get_local_accel_matrix(&qs, index1, index2)
+                })
+                .sum::<Mat3>();
+            let rel_accel_mat = local_accel_mat * inv_pos_mats[index];
+            match get_parent_index(index) {
                 None => rel_accel_mat,
                 Some(parent_index) => pos_mats[parent_index] * rel_accel_mat,
-            };
-            accel_mat
+            }
         })
         .collect()
 }
 
+/// Returns the velocity matrix of a frame relative to its parent, i.e. the sum of its
+/// coordinates' velocity matrices weighted by their `qd`s.
+fn get_frame_vel_mat(
+    frame_index: FrameIndex,
+    coordinate_offsets: &[CoordinateIndex],
+    vel_mats: &[Mat3],
+    states: &[State],
+) -> Mat3 {
+    (coordinate_offsets[frame_index]..coordinate_offsets[frame_index + 1])
+        .map(|coordinate_index| states[coordinate_index].qd * vel_mats[coordinate_index])
+        .sum()
+}
+
 fn get_vel_sum_mats(
     frames: &[&FrameBox],
     index_path_map: &FrameIndexPathMap,
@@ -181,15 +318,15 @@ fn get_vel_sum_mats(
     vel_mats: &[Mat3],
     states: &[State],
 ) -> Vec<Mat3> {
+    let coordinate_offsets = get_coordinate_offsets(frames);
     debug_assert_eq!(index_path_map.len(), frames.len());
     debug_assert_eq!(pos_mats.len(), frames.len());
-    debug_assert_eq!(vel_mats.len(), frames.len());
-    debug_assert_eq!(states.len(), frames.len());
+    debug_assert_eq!(vel_mats.len(), *coordinate_offsets.last().unwrap());
+    debug_assert_eq!(states.len(), *coordinate_offsets.last().unwrap());
     let get_parent_index = |index| get_parent_index(index, index_path_map);
-    let mut vel_sum_mats = Vec::<Mat3>::new();
-    vel_sum_mats.reserve(frames.len());
+    let mut vel_sum_mats = Vec::<Mat3>::with_capacity(frames.len());
     for index in 0..frames.len() {
-        let qd_vel_mat = states[index].qd * vel_mats[index];
+        let qd_vel_mat = get_frame_vel_mat(index, &coordinate_offsets, vel_mats, states);
         let vel_sum_mat = match get_parent_index(index) {
             None => qd_vel_mat,
             Some(parent_index) => qd_vel_mat + vel_sum_mats[parent_index],
@@ -208,23 +345,23 @@ fn get_accel_sum_mats(
     vel_sum_mats: &[Mat3],
     states: &[State],
 ) -> Vec<Mat3> {
+    let coordinate_offsets = get_coordinate_offsets(frames);
     debug_assert_eq!(index_path_map.len(), frames.len());
     debug_assert_eq!(pos_mats.len(), frames.len());
-    debug_assert_eq!(vel_mats.len(), frames.len());
+    debug_assert_eq!(vel_mats.len(), *coordinate_offsets.last().unwrap());
     debug_assert_eq!(accel_mats.len(), frames.len());
     debug_assert_eq!(vel_sum_mats.len(), frames.len());
-    debug_assert_eq!(states.len(), frames.len());
+    debug_assert_eq!(states.len(), *coordinate_offsets.last().unwrap());
     let get_parent_index = |index| get_parent_index(index, index_path_map);
-    let mut accel_sum_mats = Vec::<Mat3>::new();
-    accel_sum_mats.reserve(frames.len());
-    for index in 0..frames.len() {
-        let qd = states[index].qd;
+    let mut accel_sum_mats = Vec::<Mat3>::with_capacity(frames.len());
+    for (index, accel_mat) in accel_mats.iter().enumerate() {
         let accel_sum_mat = match get_parent_index(index) {
-            None => qd * qd * accel_mats[index],
+            None => *accel_mat,
             Some(parent_index) => {
+                let qd_vel_mat = get_frame_vel_mat(index, &coordinate_offsets, vel_mats, states);
                 accel_sum_mats[parent_index]
-                    + qd * qd * accel_mats[index]
-                    + 2. * qd * vel_sum_mats[parent_index] * vel_mats[index]
+                    + accel_mat
+                    + 2. * vel_sum_mats[parent_index] * qd_vel_mat
             }
         };
         accel_sum_mats.push(accel_sum_mat);
@@ -242,24 +379,47 @@ fn get_weight_offsets(frames: &[&FrameBox]) -> Vec<FrameIndex> {
         .collect()
 }
 
+/// Returns the mass of each weight at the given time, following the scene's mass schedules.
+fn get_weight_masses(frames: &[&FrameBox], scene: &Scene, time: f64) -> Vec<f64> {
+    let mut weight_masses = frames
+        .iter()
+        .flat_map(|frame| frame.get_weights().iter().map(|weight| weight.mass))
+        .collect::<Vec<f64>>();
+    if !scene.mass_schedules.is_empty() {
+        let id_index_map = get_id_index_map(frames);
+        let weight_offsets = get_weight_offsets(frames);
+        for mass_schedule in scene.mass_schedules.iter() {
+            let frame_index = id_index_map[&mass_schedule.frame_id];
+            weight_masses[weight_offsets[frame_index] + mass_schedule.weight_index] =
+                mass_schedule.get_mass(time);
+        }
+    }
+    weight_masses
+}
+
 fn get_weight_pos_vecs(frames: &[&FrameBox], pos_mats: &[Mat3]) -> Vec<Vec3> {
     debug_assert_eq!(pos_mats.len(), frames.len());
     frames
         .iter()
         .zip(pos_mats.iter())
-        .map(|(frame, pos_mat)| {
+        .flat_map(|(frame, pos_mat)| {
             frame
                 .get_weights()
                 .iter()
-                .map(|weight| pos_mat * weight.position.to_vec3())
-                .collect::<Vec<Vec3>>()
+                .map(move |weight| pos_mat * weight.position.to_vec3())
         })
-        .flatten()
         .collect()
 }
 
+// The rotational part of a frame's velocity matrix is `w * [[0, -1], [1, 0]]` in the plane, so
+// the angular velocity can be read off directly.  The same holds for the angular acceleration
+// contained in an acceleration-sum matrix, since the centripetal part only lands on the diagonal.
+fn get_angular_vel(vel_mat: &Mat3) -> f64 {
+    vel_mat[(1, 0)]
+}
+
 fn path_contains(path: &FramePath, parent_index: FrameIndex) -> bool {
-    path.iter().any(|index| *index == parent_index)
+    path.contains(&parent_index)
 }
 
 fn get_descendent_frames(
@@ -275,33 +435,51 @@ fn get_descendent_frames(
 }
 
 fn get_coefficient_matrix_entry(
-    row_index: FrameIndex,
-    col_index: FrameIndex,
-    frames: &[&FrameBox],
-    index_path_map: &FrameIndexPathMap,
+    row_index: CoordinateIndex,
+    col_index: CoordinateIndex,
+    layout: &FrameLayout,
     vel_mats: &[Mat3],
-    weight_offsets: &[FrameIndex],
     weight_pos_vecs: &[Vec3],
+    weight_masses: &[f64],
 ) -> f64 {
-    debug_assert!(row_index < frames.len());
-    debug_assert!(col_index < frames.len());
+    let FrameLayout {
+        frames,
+        index_path_map,
+        coordinate_frame_indices,
+        weight_offsets,
+    } = layout;
+    debug_assert!(row_index < coordinate_frame_indices.len());
+    debug_assert!(col_index < coordinate_frame_indices.len());
     debug_assert_eq!(index_path_map.len(), frames.len());
-    debug_assert_eq!(vel_mats.len(), frames.len());
+    debug_assert_eq!(vel_mats.len(), coordinate_frame_indices.len());
     debug_assert_eq!(weight_offsets.len() - 1, frames.len());
     debug_assert_eq!(weight_pos_vecs.len(), *weight_offsets.last().unwrap());
-    if col_index >= row_index && path_contains(&index_path_map[&col_index], row_index) {
+    debug_assert_eq!(weight_masses.len(), weight_pos_vecs.len());
+    let row_frame_index = coordinate_frame_indices[row_index];
+    let col_frame_index = coordinate_frame_indices[col_index];
+    if col_index >= row_index && path_contains(&index_path_map[&col_frame_index], row_frame_index) {
         let vel_mat1 = vel_mats[row_index];
         let vel_mat2 = vel_mats[col_index];
-        get_descendent_frames(col_index, &index_path_map)
+        let angular_vel1 = get_angular_vel(&vel_mat1);
+        let angular_vel2 = get_angular_vel(&vel_mat2);
+        get_descendent_frames(col_frame_index, index_path_map)
             .iter()
-            .map(|&frame_index| {
+            .flat_map(|&frame_index| {
                 let weights = frames[frame_index].get_weights();
                 let offset = weight_offsets[frame_index];
-                (0..weights.len())
-                    .map(move |index| (weights[index].mass, weight_pos_vecs[offset + index]))
+                (0..weights.len()).map(move |index| {
+                    (
+                        &weights[index],
+                        weight_pos_vecs[offset + index],
+                        weight_masses[offset + index],
+                    )
+                })
+            })
+            .map(|(weight, weight_pos, weight_mass)| {
+                let translational = (vel_mat1 * weight_pos).dot(&(vel_mat2 * weight_pos));
+                let rotational = angular_vel1 * angular_vel2;
+                weight_mass * translational + weight.inertia * rotational
             })
-            .flatten()
-            .map(|(mass, weight_pos)| mass * (vel_mat1 * weight_pos).dot(&(vel_mat2 * weight_pos)))
             .sum()
     } else {
         0.
@@ -309,110 +487,323 @@ fn get_coefficient_matrix_entry(
 }
 
 fn get_coefficient_matrix(
-    frames: &[&FrameBox],
-    index_path_map: &FrameIndexPathMap,
+    layout: &FrameLayout,
     vel_mats: &[Mat3],
-    weight_offsets: &[FrameIndex],
     weight_pos_vecs: &[Vec3],
+    weight_masses: &[f64],
 ) -> CoefficientMatrix {
     let get_coefficient = |row_index, col_index| {
         get_coefficient_matrix_entry(
             row_index,
             col_index,
-            &frames,
-            &index_path_map,
-            &vel_mats,
-            &weight_offsets,
-            &weight_pos_vecs,
+            layout,
+            vel_mats,
+            weight_pos_vecs,
+            weight_masses,
         )
     };
-    let size = frames.len();
+    let size = layout.coordinate_frame_indices.len();
     let mut coefficient_matrix = CoefficientMatrix::from_fn(size, size, get_coefficient);
     coefficient_matrix.fill_lower_triangle_with_upper_triangle();
     coefficient_matrix
 }
 
-fn get_force_vector_entry(
-    row_index: usize,
+/// Returns the drag, gravity, field and fluid forces on a weight (or a spring network's particle)
+/// with the given mass, world-space position and velocity.
+fn get_weight_force_vec(
+    scene: &Scene,
+    weight: &Weight,
+    mass: f64,
+    pos: &Vec3,
+    vel: &Vec3,
+    time: f64,
+) -> Vec3 {
+    let air_vel = match &scene.wind {
+        Some(wind) => wind.get_velocity(pos, time),
+        None => Vec3::zeros(),
+    };
+    let rel_vel = vel - air_vel;
+    let drag_force_vec = -weight.drag * rel_vel - weight.quadratic_drag * rel_vel.norm() * rel_vel;
+    let gravity_force_vec = mass * scene.gravity;
+    let field_force_vec = scene
+        .fields
+        .iter()
+        .map(|field| field.get_force(mass, pos, vel))
+        .sum::<Vec3>();
+    let fluid_force_vec = match &scene.fluid {
+        Some(fluid) => fluid.get_force(&scene.gravity, weight.volume, weight.radius, pos, vel),
+        None => Vec3::zeros(),
+    };
+    drag_force_vec + gravity_force_vec + field_force_vec + fluid_force_vec
+}
+
+fn get_weight_force_vecs(
     frames: &[&FrameBox],
-    index_path_map: &FrameIndexPathMap,
-    vel_mats: &[Mat3],
+    scene: &Scene,
     vel_sum_mats: &[Mat3],
+    weight_pos_vecs: &[Vec3],
+    weight_masses: &[f64],
+    time: f64,
+) -> Vec<Vec3> {
+    debug_assert_eq!(vel_sum_mats.len(), frames.len());
+    debug_assert_eq!(weight_masses.len(), weight_pos_vecs.len());
+    let mut weight_force_vecs = frames
+        .iter()
+        .enumerate()
+        .flat_map(|(frame_index, frame)| {
+            frame
+                .get_weights()
+                .iter()
+                .map(move |weight| (frame_index, weight))
+        })
+        .zip(weight_pos_vecs.iter().zip(weight_masses))
+        .map(|((frame_index, weight), (pos, mass))| {
+            let vel = vel_sum_mats[frame_index] * pos;
+            get_weight_force_vec(scene, weight, *mass, pos, &vel, time)
+        })
+        .collect::<Vec<Vec3>>();
+    add_interaction_forces_mut(
+        frames,
+        scene,
+        weight_pos_vecs,
+        weight_masses,
+        &mut weight_force_vecs,
+    );
+    weight_force_vecs
+}
+
+fn add_interaction_forces_mut(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    weight_pos_vecs: &[Vec3],
+    weight_masses: &[f64],
+    weight_force_vecs: &mut [Vec3],
+) {
+    debug_assert_eq!(weight_force_vecs.len(), weight_pos_vecs.len());
+    let weights = frames
+        .iter()
+        .flat_map(|frame| {
+            frame
+                .get_weights()
+                .iter()
+                .map(move |weight| (frame.get_id(), weight))
+        })
+        .collect::<Vec<_>>();
+    for interaction in scene.interactions.iter() {
+        let weight_indices = (0..weights.len())
+            .filter(|&index| interaction.applies_to(weights[index].0, weights[index].1))
+            .collect::<Vec<_>>();
+        for (offset, &index1) in weight_indices.iter().enumerate() {
+            for &index2 in &weight_indices[offset + 1..] {
+                let force_vec = interaction.get_force(
+                    &weight_pos_vecs[index1],
+                    weight_masses[index1],
+                    &weight_pos_vecs[index2],
+                    weight_masses[index2],
+                );
+                weight_force_vecs[index1] -= force_vec;
+                weight_force_vecs[index2] += force_vec;
+            }
+        }
+    }
+}
+
+fn get_force_vector_entry(
+    row_index: CoordinateIndex,
+    layout: &FrameLayout,
+    vel_mats: &[Mat3],
     accel_sum_mats: &[Mat3],
-    weight_offsets: &[FrameIndex],
     weight_pos_vecs: &[Vec3],
-    gravity: &Vec3,
-    states: &[State],
-    external_forces: &[f64],
+    weight_masses: &[f64],
+    weight_force_vecs: &[Vec3],
 ) -> f64 {
-    debug_assert!(row_index < frames.len());
-    debug_assert_eq!(vel_mats.len(), frames.len());
+    let FrameLayout {
+        frames,
+        index_path_map,
+        coordinate_frame_indices,
+        weight_offsets,
+    } = layout;
+    debug_assert!(row_index < coordinate_frame_indices.len());
     debug_assert_eq!(index_path_map.len(), frames.len());
-    debug_assert_eq!(vel_mats.len(), frames.len());
-    debug_assert_eq!(vel_sum_mats.len(), frames.len());
+    debug_assert_eq!(vel_mats.len(), coordinate_frame_indices.len());
     debug_assert_eq!(accel_sum_mats.len(), frames.len());
     debug_assert_eq!(weight_offsets.len() - 1, frames.len());
     debug_assert_eq!(weight_pos_vecs.len(), *weight_offsets.last().unwrap());
-    debug_assert_eq!(states.len(), frames.len());
-    debug_assert_eq!(external_forces.len(), frames.len());
-    let descendent_frames = get_descendent_frames(row_index, &index_path_map);
-    let descendent_weights = descendent_frames
-        .iter()
-        .map(|&frame_index| {
+    debug_assert_eq!(weight_force_vecs.len(), weight_pos_vecs.len());
+    let row_frame_index = coordinate_frame_indices[row_index];
+    let descendent_frames = get_descendent_frames(row_frame_index, index_path_map);
+    let descendent_weights =
+        descendent_frames.iter().flat_map(|&frame_index| {
             let weight_offset = weight_offsets[frame_index];
             frames[frame_index].get_weights().iter().enumerate().map(
                 move |(weight_index, weight)| (frame_index, weight_offset + weight_index, weight),
             )
-        })
-        .flatten();
+        });
     let weight_forces = descendent_weights.map(|(frame_index, weight_index, weight)| {
         let pos = weight_pos_vecs[weight_index];
-        let kinetic_force_vec = -weight.mass * accel_sum_mats[frame_index] * pos;
-        let drag_force_vec = -weight.drag * vel_sum_mats[frame_index] * pos;
-        let gravity_force_vec = weight.mass * gravity;
-        (vel_mats[row_index] * pos).dot(&(kinetic_force_vec + drag_force_vec + gravity_force_vec))
+        let kinetic_force_vec = -weight_masses[weight_index] * accel_sum_mats[frame_index] * pos;
+        let kinetic_torque = -weight.inertia * get_angular_vel(&accel_sum_mats[frame_index]);
+        (vel_mats[row_index] * pos).dot(&(kinetic_force_vec + weight_force_vecs[weight_index]))
+            + get_angular_vel(&vel_mats[row_index]) * kinetic_torque
     });
-    let resistance_force = -states[row_index].qd * frames[row_index].get_resistance();
-    resistance_force + weight_forces.sum::<f64>() + external_forces[row_index]
+    weight_forces.sum()
 }
 
 fn get_force_vector(
-    frames: &[&FrameBox],
-    index_path_map: &FrameIndexPathMap,
+    layout: &FrameLayout,
     vel_mats: &[Mat3],
-    vel_sum_mats: &[Mat3],
     accel_sum_mats: &[Mat3],
-    weight_offsets: &[FrameIndex],
     weight_pos_vecs: &[Vec3],
-    gravity: &Vec3,
-    states: &[State],
+    weight_masses: &[f64],
+    weight_force_vecs: &[Vec3],
     external_forces: &[f64],
 ) -> ForceVector {
     let get_entry = |row, col| {
         debug_assert_eq!(col, 0);
         get_force_vector_entry(
             row,
-            frames,
-            index_path_map,
+            layout,
             vel_mats,
-            vel_sum_mats,
             accel_sum_mats,
-            weight_offsets,
             weight_pos_vecs,
-            gravity,
-            states,
-            external_forces,
-        )
+            weight_masses,
+            weight_force_vecs,
+        ) + external_forces[row]
     };
-    ForceVector::from_fn(frames.len(), get_entry)
+    debug_assert_eq!(external_forces.len(), layout.coordinate_frame_indices.len());
+    ForceVector::from_fn(layout.coordinate_frame_indices.len(), get_entry)
+}
+
+fn get_actuated_forces(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    states: &[State],
+    external_forces: &[f64],
+) -> Vec<f64> {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    debug_assert_eq!(external_forces.len(), states.len());
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    let mut forces = external_forces.to_vec();
+    scene.actuators.iter().for_each(|actuator| {
+        let index = id_coordinate_map[&actuator.frame_id];
+        forces[index] = actuator.get_force(external_forces[index], states[index].qd);
+    });
+    forces
+}
+
+fn add_scheduled_forces_mut(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    external_forces: &mut [f64],
+    time: f64,
+) {
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    scene.force_schedules.iter().for_each(|force_schedule| {
+        external_forces[id_coordinate_map[&force_schedule.frame_id]] +=
+            force_schedule.get_force(time);
+    });
+}
+
+/// Adds the forces with which each frame's resistance opposes the motion of its coordinates.
+fn add_resistance_forces_mut(frames: &[&FrameBox], states: &[State], external_forces: &mut [f64]) {
+    let coordinate_frame_indices = get_coordinate_frame_indices(frames);
+    debug_assert_eq!(states.len(), coordinate_frame_indices.len());
+    debug_assert_eq!(external_forces.len(), coordinate_frame_indices.len());
+    for ((force, state), frame_index) in external_forces
+        .iter_mut()
+        .zip(states)
+        .zip(coordinate_frame_indices)
+    {
+        *force -= state.qd * frames[frame_index].get_resistance();
+    }
+}
+
+/// Adds the forces of couplings with backlash and of deadbands, which push their coordinates back
+/// into the gap once they've moved outside it.
+fn add_backlash_forces_mut(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    states: &[State],
+    external_forces: &mut [f64],
+) {
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    scene.couplings.iter().for_each(|coupling| {
+        if let Some(backlash) = &coupling.backlash {
+            let index = id_coordinate_map[&coupling.frame_id];
+            let driver_index = id_coordinate_map[&coupling.driver_frame_id];
+            let error = coupling.get_error(states[index].q, states[driver_index].q);
+            let error_rate = states[index].qd - coupling.ratio * states[driver_index].qd;
+            let force = backlash.get_force(error, error_rate);
+            external_forces[index] += force;
+            external_forces[driver_index] -= coupling.ratio * force;
+        }
+    });
+    scene.deadbands.iter().for_each(|deadband| {
+        let state = &states[id_coordinate_map[&deadband.frame_id]];
+        external_forces[id_coordinate_map[&deadband.frame_id]] += deadband
+            .backlash
+            .get_force(state.q - deadband.position, state.qd);
+    });
+}
+
+fn add_actuator_inertias_mut(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    coefficient_matrix: &mut CoefficientMatrix,
+) {
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    scene.actuators.iter().for_each(|actuator| {
+        let index = id_coordinate_map[&actuator.frame_id];
+        coefficient_matrix[(index, index)] += actuator.get_reflected_inertia();
+    });
+}
+
+/// Adds the scene's damping forces, `-C * qd`, where Rayleigh damping is proportional to the mass
+/// matrix (including the actuators' reflected inertia).
+fn add_damping_forces_mut(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    states: &[State],
+    coefficient_matrix: &CoefficientMatrix,
+    force_vector: &mut ForceVector,
+) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    if scene.damping.is_empty() {
+        return;
+    }
+    let qds = ForceVector::from_iterator(states.len(), states.iter().map(|state| state.qd));
+    let id_index_map = get_id_index_map(frames);
+    let coordinate_offsets = get_coordinate_offsets(frames);
+    for damping in scene.damping.iter() {
+        match damping {
+            Damping::Matrix { frame_ids, matrix } => {
+                let indices = frame_ids
+                    .iter()
+                    .flat_map(|frame_id| {
+                        let frame_index = id_index_map[frame_id];
+                        coordinate_offsets[frame_index]..coordinate_offsets[frame_index + 1]
+                    })
+                    .collect::<Vec<CoordinateIndex>>();
+                for (row, row_index) in indices.iter().enumerate() {
+                    for (col, col_index) in indices.iter().enumerate() {
+                        force_vector[*row_index] -= matrix[(row, col)] * qds[*col_index];
+                    }
+                }
+            }
+            Damping::Rayleigh { alpha } => {
+                *force_vector -= *alpha * (coefficient_matrix * &qds);
+            }
+        }
+    }
 }
 
 fn get_system_of_equations(
     frames: &[&FrameBox],
     index_path_map: &FrameIndexPathMap,
-    gravity: &Vec3,
+    scene: &Scene,
     states: &[State],
     external_forces: &[f64],
+    time: f64,
 ) -> (CoefficientMatrix, ForceVector) {
     let pos_mats = get_pos_mats(frames, index_path_map, states);
     let inv_pos_mats = get_inv_pos_mats(&pos_mats);
@@ -428,116 +819,1242 @@ fn get_system_of_equations(
         &vel_sum_mats,
         states,
     );
-    let weight_offsets = get_weight_offsets(frames);
+    let layout = FrameLayout::new(frames, index_path_map);
     let weight_pos_vecs = get_weight_pos_vecs(frames, &pos_mats);
-    let coefficient_matrix = get_coefficient_matrix(
+    let weight_masses = get_weight_masses(frames, scene, time);
+    let mut coefficient_matrix =
+        get_coefficient_matrix(&layout, &vel_mats, &weight_pos_vecs, &weight_masses);
+    add_actuator_inertias_mut(frames, scene, &mut coefficient_matrix);
+    let mut external_forces = get_actuated_forces(frames, scene, states, external_forces);
+    add_scheduled_forces_mut(frames, scene, &mut external_forces, time);
+    add_backlash_forces_mut(frames, scene, states, &mut external_forces);
+    add_resistance_forces_mut(frames, states, &mut external_forces);
+    let weight_force_vecs = get_weight_force_vecs(
         frames,
-        index_path_map,
-        &vel_mats,
-        &weight_offsets,
+        scene,
+        &vel_sum_mats,
         &weight_pos_vecs,
+        &weight_masses,
+        time,
     );
-    let force_vector = get_force_vector(
-        frames,
-        index_path_map,
+    let mut force_vector = get_force_vector(
+        &layout,
         &vel_mats,
-        &vel_sum_mats,
         &accel_sum_mats,
-        &weight_offsets,
         &weight_pos_vecs,
-        gravity,
+        &weight_masses,
+        &weight_force_vecs,
+        &external_forces,
+    );
+    add_damping_forces_mut(
+        frames,
+        scene,
         states,
-        external_forces,
+        &coefficient_matrix,
+        &mut force_vector,
     );
     (coefficient_matrix, force_vector)
 }
 
-fn solve(
+fn get_stabilized_constraint_accel(error: f64, error_rate: f64) -> f64 {
+    let frequency = CONSTRAINT_STABILIZATION_FREQUENCY;
+    -2. * frequency * error_rate - frequency * frequency * error
+}
+
+fn get_coupling_equations(
     frames: &[&FrameBox],
-    index_path_map: &FrameIndexPathMap,
-    gravity: &Vec3,
+    scene: &Scene,
     states: &[State],
-    external_forces: &[f64],
-) -> Vec<f64> {
-    let (coefficient_matrix, force_vector) =
-        get_system_of_equations(frames, index_path_map, gravity, states, external_forces);
-    coefficient_matrix
-        .qr()
-        .solve(&force_vector)
-        .unwrap()
-        .as_slice()
-        .to_vec()
+) -> (ConstraintMatrix, ConstraintVector) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    // Couplings with backlash act through forces instead:
+    let couplings = scene
+        .couplings
+        .iter()
+        .filter(|coupling| coupling.backlash.is_none())
+        .collect::<Vec<_>>();
+    let count = couplings.len();
+    let mut constraint_matrix = ConstraintMatrix::zeros(count, states.len());
+    let mut constraint_vector = ConstraintVector::zeros(count);
+    couplings
+        .iter()
+        .enumerate()
+        .for_each(|(row_index, coupling)| {
+            let index = id_coordinate_map[&coupling.frame_id];
+            let driver_index = id_coordinate_map[&coupling.driver_frame_id];
+            let error = coupling.get_error(states[index].q, states[driver_index].q);
+            let error_rate = states[index].qd - coupling.ratio * states[driver_index].qd;
+            constraint_matrix[(row_index, index)] = 1.;
+            constraint_matrix[(row_index, driver_index)] = -coupling.ratio;
+            constraint_vector[row_index] = get_stabilized_constraint_accel(error, error_rate);
+        });
+    (constraint_matrix, constraint_vector)
 }
 
-fn tick_simple_mut(
-    frames: &[&FrameBox],
+/// Returns the derivative of the world-space position of a point attached to a frame with respect
+/// to each coordinate, which is zero for coordinates that don't move the frame.
+fn get_point_pos_derivatives(
+    frame_index: FrameIndex,
+    pos: &Vec3,
     index_path_map: &FrameIndexPathMap,
-    gravity: &Vec3,
-    states: &mut [State],
-    external_forces: &[f64],
-    delta_time: f64,
-) {
-    let apply_deltas_mut = |states: &mut [State], qdd_vec: &[f64], delta_time: f64| {
-        states.iter_mut().enumerate().for_each(|(index, state)| {
-            state.q += state.qd * delta_time;
-            state.qd += qdd_vec[index] * delta_time;
-        });
-    };
-    let qdd_vec = solve(frames, index_path_map, gravity, states, external_forces);
-    apply_deltas_mut(states, &qdd_vec, delta_time);
+    coordinate_frame_indices: &[FrameIndex],
+    vel_mats: &[Mat3],
+) -> Vec<Vec3> {
+    debug_assert_eq!(vel_mats.len(), coordinate_frame_indices.len());
+    let path = &index_path_map[&frame_index];
+    coordinate_frame_indices
+        .iter()
+        .zip(vel_mats)
+        .map(|(coordinate_frame_index, vel_mat)| {
+            match path_contains(path, *coordinate_frame_index) {
+                true => vel_mat * pos,
+                false => Vec3::zeros(),
+            }
+        })
+        .collect()
 }
 
-fn tick_runge_kutta_mut(
+/// Returns the generalized forces equivalent to world-space forces applied at points on frames,
+/// i.e. `J^T * F`, where `J` maps the coordinates' velocities to the point's velocity.
+fn get_point_generalized_forces(
     frames: &[&FrameBox],
     index_path_map: &FrameIndexPathMap,
-    gravity: &Vec3,
-    states: &mut [State],
-    external_forces: &[f64],
-    delta_time: f64,
-) {
-    let count = frames.len();
-    let get_qs = |states: &[State]| states.iter().map(|state| state.q).collect::<Vec<f64>>();
-    let get_qds = |states: &[State]| states.iter().map(|state| state.qd).collect::<Vec<f64>>();
-    let unzip_states = |states: &[State]| (get_qs(states), get_qds(states));
-    let zip_states = |qs: &[f64], qds: &[f64]| {
-        qs.iter()
-            .zip(qds)
-            .map(|(q, qd)| State { q: *q, qd: *qd })
-            .collect::<Vec<State>>()
-    };
-    let solve = |qs: &[f64], qds: &[f64]| {
-        solve(
-            frames,
+    states: &[State],
+    point_forces: &[PointForce],
+) -> Vec<f64> {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let pos_mats = get_pos_mats(frames, index_path_map, states);
+    let inv_pos_mats = get_inv_pos_mats(&pos_mats);
+    let vel_mats = get_vel_mats(frames, index_path_map, &pos_mats, &inv_pos_mats, states);
+    let id_index_map = get_id_index_map(frames);
+    let coordinate_frame_indices = get_coordinate_frame_indices(frames);
+    let mut forces = vec![0.; states.len()];
+    for point_force in point_forces.iter() {
+        let frame_index = id_index_map[&point_force.frame_id];
+        let pos = pos_mats[frame_index] * point_force.position.to_vec3();
+        let pos_derivatives = get_point_pos_derivatives(
+            frame_index,
+            &pos,
             index_path_map,
-            gravity,
-            &zip_states(qs, qds),
-            external_forces,
-        )
-    };
-    let apply_deltas =
-        |qs: &[f64], qds: &[f64], delta_qs: &[f64], delta_qds: &[f64], delta_time: f64| {
-            let mut new_qs = Vec::<f64>::new();
-            let mut new_qds = Vec::<f64>::new();
-            new_qs.reserve(count);
-            new_qds.reserve(count);
-            for i in 0..count {
-                new_qs.push(qs[i] + delta_qs[i] * delta_time);
-                new_qds.push(qds[i] + delta_qds[i] * delta_time);
-            }
-            (new_qs, new_qds)
-        };
-
-    let (qs0, qds0) = unzip_states(states);
-    let qdds0 = solve(&qs0, &qds0);
-
-    let (qs1, qds1) = apply_deltas(&qs0, &qds0, &qds0, &qdds0, delta_time / 2.);
-    let qdds1 = solve(&qs1, &qds1);
-
-    let (qs2, qds2) = apply_deltas(&qs0, &qds0, &qds1, &qdds1, delta_time / 2.);
-    let qdds2 = solve(&qs2, &qds2);
+            &coordinate_frame_indices,
+            &vel_mats,
+        );
+        for (force, pos_derivative) in forces.iter_mut().zip(pos_derivatives) {
+            *force += pos_derivative.dot(&point_force.force);
+        }
+    }
+    forces
+}
+
+/// Returns the constraint equations of the ropes that are currently taut, each of which holds the
+/// path length through the rope's points at the rope's length (i.e. `J * qdd = -dJ/dt * qd`, where
+/// `J` is the gradient of the path length with respect to the coordinates).
+fn get_rope_equations(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+) -> (ConstraintMatrix, ConstraintVector) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let pos_mats = get_pos_mats(frames, index_path_map, states);
+    let inv_pos_mats = get_inv_pos_mats(&pos_mats);
+    let vel_mats = get_vel_mats(frames, index_path_map, &pos_mats, &inv_pos_mats, states);
+    let vel_sum_mats = get_vel_sum_mats(frames, index_path_map, &pos_mats, &vel_mats, states);
+    let accel_mats = get_accel_mats(frames, index_path_map, &pos_mats, &inv_pos_mats, states);
+    let accel_sum_mats = get_accel_sum_mats(
+        frames,
+        index_path_map,
+        &pos_mats,
+        &vel_mats,
+        &accel_mats,
+        &vel_sum_mats,
+        states,
+    );
+    let id_index_map = get_id_index_map(frames);
+    let coordinate_frame_indices = get_coordinate_frame_indices(frames);
+    let count = states.len();
+    let rows = scene
+        .ropes
+        .iter()
+        .filter_map(|rope| {
+            // World-space position, velocity, velocity-dependent acceleration, and derivative of
+            // position with respect to each coordinate, for each of the rope's points:
+            let points = rope
+                .points
+                .iter()
+                .map(|point| match &point.frame_id {
+                    None => (
+                        point.position.to_vec3(),
+                        Vec3::zeros(),
+                        Vec3::zeros(),
+                        vec![Vec3::zeros(); count],
+                    ),
+                    Some(frame_id) => {
+                        let frame_index = id_index_map[frame_id];
+                        let pos = pos_mats[frame_index] * point.position.to_vec3();
+                        let pos_derivatives = get_point_pos_derivatives(
+                            frame_index,
+                            &pos,
+                            index_path_map,
+                            &coordinate_frame_indices,
+                            &vel_mats,
+                        );
+                        (
+                            pos,
+                            vel_sum_mats[frame_index] * pos,
+                            accel_sum_mats[frame_index] * pos,
+                            pos_derivatives,
+                        )
+                    }
+                })
+                .collect::<Vec<_>>();
+            let mut length = 0.;
+            let mut row = vec![0.; count];
+            let mut accel_bias = 0.;
+            for segment in points.windows(2) {
+                let (pos1, vel1, accel1, pos_derivatives1) = &segment[0];
+                let (pos2, vel2, accel2, pos_derivatives2) = &segment[1];
+                let distance = (pos2 - pos1).norm();
+                if distance == 0. {
+                    continue;
+                }
+                let direction = (pos2 - pos1) / distance;
+                let rel_vel = vel2 - vel1;
+                // The segment's length changes with the relative velocity along it, and that rate
+                // changes with the relative acceleration along it plus the rotation of the
+                // segment itself:
+                let normal_vel = rel_vel - direction * direction.dot(&rel_vel);
+                length += distance;
+                accel_bias +=
+                    direction.dot(&(accel2 - accel1)) + normal_vel.norm_squared() / distance;
+                for index in 0..count {
+                    row[index] +=
+                        direction.dot(&(pos_derivatives2[index] - pos_derivatives1[index]));
+                }
+            }
+            match length < rope.length - ROPE_SLACK_TOLERANCE {
+                true => None,
+                false => {
+                    let length_rate = (0..count).map(|index| row[index] * states[index].qd).sum();
+                    let accel = get_stabilized_constraint_accel(length - rope.length, length_rate);
+                    Some((row, accel - accel_bias))
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+    let constraint_matrix = ConstraintMatrix::from_fn(rows.len(), count, |row_index, col_index| {
+        rows[row_index].0[col_index]
+    });
+    let constraint_vector =
+        ConstraintVector::from_iterator(rows.len(), rows.iter().map(|(_, accel)| *accel));
+    (constraint_matrix, constraint_vector)
+}
+
+/// Returns the matrix of the equations of motion together with the constraints, whose unknowns
+/// are the accelerations followed by the constraints' multipliers.
+fn get_constrained_matrix(
+    coefficient_matrix: &CoefficientMatrix,
+    constraint_matrix: &ConstraintMatrix,
+) -> CoefficientMatrix {
+    debug_assert_eq!(constraint_matrix.ncols(), coefficient_matrix.ncols());
+    let size = coefficient_matrix.nrows();
+    let count = constraint_matrix.nrows();
+    let mut matrix = CoefficientMatrix::zeros(size + count, size + count);
+    matrix
+        .slice_mut((0, 0), (size, size))
+        .copy_from(coefficient_matrix);
+    matrix
+        .slice_mut((size, 0), (count, size))
+        .copy_from(constraint_matrix);
+    matrix
+        .slice_mut((0, size), (size, count))
+        .copy_from(&constraint_matrix.transpose());
+    matrix
+}
+
+fn solve_constrained(
+    coefficient_matrix: &CoefficientMatrix,
+    force_vector: &ForceVector,
+    constraint_matrix: &ConstraintMatrix,
+    constraint_vector: &ConstraintVector,
+) -> (Vec<f64>, ConstraintVector) {
+    debug_assert_eq!(constraint_matrix.ncols(), coefficient_matrix.ncols());
+    debug_assert_eq!(constraint_vector.len(), constraint_matrix.nrows());
+    let size = coefficient_matrix.nrows();
+    let count = constraint_matrix.nrows();
+    let mut vector = ForceVector::zeros(size + count);
+    vector.rows_mut(0, size).copy_from(force_vector);
+    vector.rows_mut(size, count).copy_from(constraint_vector);
+    let solution = get_constrained_matrix(coefficient_matrix, constraint_matrix)
+        .qr()
+        .solve(&vector)
+        .unwrap();
+    (
+        solution.rows(0, size).iter().copied().collect(),
+        solution.rows(size, count).into_owned(),
+    )
+}
+
+fn set_prescribed_states_mut(frames: &[&FrameBox], scene: &Scene, states: &mut [State], time: f64) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    scene
+        .prescribed_motions
+        .iter()
+        .for_each(|prescribed_motion| {
+            states[id_coordinate_map[&prescribed_motion.frame_id]] =
+                prescribed_motion.get_state(time);
+        });
+}
+
+fn get_prescribed_accels(frames: &[&FrameBox], scene: &Scene, time: f64) -> Vec<Option<f64>> {
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    let mut accels = vec![None; get_coordinate_count(frames)];
+    scene
+        .prescribed_motions
+        .iter()
+        .for_each(|prescribed_motion| {
+            accels[id_coordinate_map[&prescribed_motion.frame_id]] =
+                Some(prescribed_motion.get_accel(time));
+        });
+    accels
+}
+
+/// Removes prescribed coordinates from the unknowns by moving their known accelerations over to
+/// the right-hand side and replacing their rows with trivial `qdd = accel` equations.
+fn eliminate_prescribed_accels_mut(
+    prescribed_accels: &[Option<f64>],
+    coefficient_matrix: &mut CoefficientMatrix,
+    force_vector: &mut ForceVector,
+    constraint_matrix: &mut ConstraintMatrix,
+    constraint_vector: &mut ConstraintVector,
+) {
+    for (index, accel) in prescribed_accels.iter().enumerate() {
+        if let Some(accel) = *accel {
+            *force_vector -= coefficient_matrix.column(index) * accel;
+            *constraint_vector -= constraint_matrix.column(index) * accel;
+            coefficient_matrix.row_mut(index).fill(0.);
+            coefficient_matrix.column_mut(index).fill(0.);
+            coefficient_matrix[(index, index)] = 1.;
+            force_vector[index] = accel;
+            constraint_matrix.column_mut(index).fill(0.);
+        }
+    }
+}
+
+/// Adds a zero acceleration to the prescribed ones for each coordinate that moves no mass and
+/// isn't constrained, e.g. the angle of a free frame carrying a single point mass at its origin.
+/// Nothing depends on how such a coordinate moves, but it would make the equations singular.
+fn get_massless_accels(
+    coefficient_matrix: &CoefficientMatrix,
+    coupling_matrix: &ConstraintMatrix,
+    rope_matrix: &ConstraintMatrix,
+    prescribed_accels: &[Option<f64>],
+) -> Vec<Option<f64>> {
+    let tolerance = 1e-12 * coefficient_matrix.diagonal().amax().max(1.);
+    let is_zero = |column: nalgebra::DVectorSlice<f64>| column.amax() <= tolerance;
+    prescribed_accels
+        .iter()
+        .enumerate()
+        .map(|(index, accel)| {
+            let is_massless = coefficient_matrix[(index, index)].abs() <= tolerance
+                && is_zero(coupling_matrix.column(index))
+                && is_zero(rope_matrix.column(index));
+            match accel.is_none() && is_massless {
+                true => Some(0.),
+                false => *accel,
+            }
+        })
+        .collect()
+}
+
+/// Solves the equations of motion subject to the couplings and whichever of the taut ropes stay
+/// taut, with the prescribed coordinates' accelerations known ahead of time.  Returns the
+/// accelerations along with the constraint matrix that was used and its multipliers.
+fn solve_with_ropes(
+    coefficient_matrix: &CoefficientMatrix,
+    force_vector: &ForceVector,
+    coupling_matrix: &ConstraintMatrix,
+    coupling_vector: &ConstraintVector,
+    rope_matrix: &ConstraintMatrix,
+    rope_vector: &ConstraintVector,
+    prescribed_accels: &[Option<f64>],
+) -> (Vec<f64>, ConstraintMatrix, ConstraintVector) {
+    let size = coefficient_matrix.nrows();
+    let coupling_count = coupling_matrix.nrows();
+    let prescribed_accels = get_massless_accels(
+        coefficient_matrix,
+        coupling_matrix,
+        rope_matrix,
+        prescribed_accels,
+    );
+    // Taut ropes can only pull, so any that would need to push are dropped as slack and the system
+    // is solved again without them:
+    let mut rope_indices = (0..rope_matrix.nrows()).collect::<Vec<_>>();
+    loop {
+        let constraint_count = coupling_count + rope_indices.len();
+        let mut constraint_matrix = ConstraintMatrix::zeros(constraint_count, size);
+        let mut constraint_vector = ConstraintVector::zeros(constraint_count);
+        constraint_matrix
+            .rows_mut(0, coupling_count)
+            .copy_from(coupling_matrix);
+        constraint_vector
+            .rows_mut(0, coupling_count)
+            .copy_from(coupling_vector);
+        for (row_index, rope_index) in rope_indices.iter().enumerate() {
+            constraint_matrix
+                .row_mut(coupling_count + row_index)
+                .copy_from(&rope_matrix.row(*rope_index));
+            constraint_vector[coupling_count + row_index] = rope_vector[*rope_index];
+        }
+        let mut reduced_coefficient_matrix = coefficient_matrix.clone();
+        let mut reduced_force_vector = force_vector.clone();
+        let mut reduced_constraint_matrix = constraint_matrix.clone();
+        let mut reduced_constraint_vector = constraint_vector;
+        eliminate_prescribed_accels_mut(
+            &prescribed_accels,
+            &mut reduced_coefficient_matrix,
+            &mut reduced_force_vector,
+            &mut reduced_constraint_matrix,
+            &mut reduced_constraint_vector,
+        );
+        let (qdds, multipliers) = solve_constrained(
+            &reduced_coefficient_matrix,
+            &reduced_force_vector,
+            &reduced_constraint_matrix,
+            &reduced_constraint_vector,
+        );
+        // A rope's multiplier is its tension:
+        let taut_rope_indices = rope_indices
+            .iter()
+            .enumerate()
+            .filter(|(row_index, _)| multipliers[coupling_count + row_index] >= 0.)
+            .map(|(_, rope_index)| *rope_index)
+            .collect::<Vec<_>>();
+        if taut_rope_indices.len() == rope_indices.len() {
+            return (qdds, constraint_matrix, multipliers);
+        }
+        rope_indices = taut_rope_indices;
+    }
+}
+
+/// Solves for the accelerations of all coordinates, along with the generalized force needed to
+/// drive each prescribed coordinate (which is zero for every other coordinate).
+fn solve_with_drive_forces(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    external_forces: &[f64],
+    time: f64,
+) -> (Vec<f64>, ForceVector) {
+    let mut states = states.to_vec();
+    set_prescribed_states_mut(frames, scene, &mut states, time);
+    let (coefficient_matrix, force_vector) = get_system_of_equations(
+        frames,
+        index_path_map,
+        scene,
+        &states,
+        external_forces,
+        time,
+    );
+    let (coupling_matrix, coupling_vector) = get_coupling_equations(frames, scene, &states);
+    let (rope_matrix, rope_vector) = get_rope_equations(frames, index_path_map, scene, &states);
+    let (qdds, constraint_matrix, multipliers) = solve_with_ropes(
+        &coefficient_matrix,
+        &force_vector,
+        &coupling_matrix,
+        &coupling_vector,
+        &rope_matrix,
+        &rope_vector,
+        &get_prescribed_accels(frames, scene, time),
+    );
+    // The equations of motion balance for every free coordinate, so whatever is left over is the
+    // force that the prescribed coordinates' drivers must supply:
+    let drive_forces = &coefficient_matrix * ForceVector::from_column_slice(&qdds)
+        + constraint_matrix.transpose() * multipliers
+        - &force_vector;
+    (qdds, drive_forces)
+}
+
+/// Returns the mass matrix, coupling matrix and rope matrix that impulses act through, along with
+/// the (zero) velocity changes of the prescribed coordinates.
+fn get_impulse_equations(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    time: f64,
+) -> (
+    CoefficientMatrix,
+    ConstraintMatrix,
+    ConstraintMatrix,
+    Vec<Option<f64>>,
+) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let pos_mats = get_pos_mats(frames, index_path_map, states);
+    let inv_pos_mats = get_inv_pos_mats(&pos_mats);
+    let vel_mats = get_vel_mats(frames, index_path_map, &pos_mats, &inv_pos_mats, states);
+    let weight_pos_vecs = get_weight_pos_vecs(frames, &pos_mats);
+    let weight_masses = get_weight_masses(frames, scene, time);
+    let mut coefficient_matrix = get_coefficient_matrix(
+        &FrameLayout::new(frames, index_path_map),
+        &vel_mats,
+        &weight_pos_vecs,
+        &weight_masses,
+    );
+    add_actuator_inertias_mut(frames, scene, &mut coefficient_matrix);
+    let (coupling_matrix, _) = get_coupling_equations(frames, scene, states);
+    let (rope_matrix, _) = get_rope_equations(frames, index_path_map, scene, states);
+    let id_coordinate_map = get_id_coordinate_map(frames);
+    let mut prescribed_vel_deltas = vec![None; states.len()];
+    scene
+        .prescribed_motions
+        .iter()
+        .for_each(|prescribed_motion| {
+            prescribed_vel_deltas[id_coordinate_map[&prescribed_motion.frame_id]] = Some(0.);
+        });
+    (
+        coefficient_matrix,
+        coupling_matrix,
+        rope_matrix,
+        prescribed_vel_deltas,
+    )
+}
+
+/// Returns the changes in velocity caused by generalized impulses, which act against the inertia
+/// of everything they move while keeping to the couplings and taut ropes.  Prescribed coordinates
+/// keep their velocities.
+fn get_impulse_vel_deltas(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    impulses: &[f64],
+    time: f64,
+) -> Vec<f64> {
+    debug_assert_eq!(impulses.len(), states.len());
+    let (coefficient_matrix, coupling_matrix, rope_matrix, prescribed_vel_deltas) =
+        get_impulse_equations(frames, index_path_map, scene, states, time);
+    // Velocities jump while positions stay put, so the same equations hold with impulses in place
+    // of forces and velocity changes in place of accelerations:
+    let (vel_deltas, _, _) = solve_with_ropes(
+        &coefficient_matrix,
+        &ForceVector::from_column_slice(impulses),
+        &coupling_matrix,
+        &ConstraintVector::zeros(coupling_matrix.nrows()),
+        &rope_matrix,
+        &ConstraintVector::zeros(rope_matrix.nrows()),
+        &prescribed_vel_deltas,
+    );
+    vel_deltas
+}
+
+/// Returns the changes in velocity caused by each of several sets of generalized impulses, as
+/// `get_impulse_vel_deltas` would, but building and factoring the equations only once for all of
+/// them.  Only sets of impulses that would need a taut rope to push are solved again on their own,
+/// with the rope going slack.
+fn get_batch_impulse_vel_deltas(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    impulse_sets: &[&[f64]],
+    time: f64,
+) -> Vec<Vec<f64>> {
+    if impulse_sets.is_empty() {
+        return Vec::new();
+    }
+    let (coefficient_matrix, coupling_matrix, rope_matrix, prescribed_vel_deltas) =
+        get_impulse_equations(frames, index_path_map, scene, states, time);
+    let size = coefficient_matrix.nrows();
+    let coupling_count = coupling_matrix.nrows();
+    let rope_count = rope_matrix.nrows();
+    let eliminated_vel_deltas = get_massless_accels(
+        &coefficient_matrix,
+        &coupling_matrix,
+        &rope_matrix,
+        &prescribed_vel_deltas,
+    );
+    let mut constraint_matrix = ConstraintMatrix::zeros(coupling_count + rope_count, size);
+    constraint_matrix
+        .rows_mut(0, coupling_count)
+        .copy_from(&coupling_matrix);
+    constraint_matrix
+        .rows_mut(coupling_count, rope_count)
+        .copy_from(&rope_matrix);
+    // Every eliminated velocity change is zero, so eliminating them only changes the matrices,
+    // and the impulses on the eliminated coordinates are replaced by zeros:
+    debug_assert!(eliminated_vel_deltas
+        .iter()
+        .flatten()
+        .all(|vel_delta| *vel_delta == 0.));
+    let mut reduced_coefficient_matrix = coefficient_matrix.clone();
+    let mut reduced_constraint_matrix = constraint_matrix;
+    eliminate_prescribed_accels_mut(
+        &eliminated_vel_deltas,
+        &mut reduced_coefficient_matrix,
+        &mut ForceVector::zeros(size),
+        &mut reduced_constraint_matrix,
+        &mut ConstraintVector::zeros(coupling_count + rope_count),
+    );
+    let vectors = CoefficientMatrix::from_fn(
+        size + coupling_count + rope_count,
+        impulse_sets.len(),
+        |row_index, column_index| match row_index < size
+            && eliminated_vel_deltas[row_index].is_none()
+        {
+            true => impulse_sets[column_index][row_index],
+            false => 0.,
+        },
+    );
+    let solutions = get_constrained_matrix(&reduced_coefficient_matrix, &reduced_constraint_matrix)
+        .qr()
+        .solve(&vectors)
+        .unwrap();
+    impulse_sets
+        .iter()
+        .enumerate()
+        .map(|(column_index, impulses)| {
+            let solution = solutions.column(column_index);
+            let ropes_pull = solution
+                .rows(size + coupling_count, rope_count)
+                .iter()
+                .all(|multiplier| *multiplier >= 0.);
+            match ropes_pull {
+                true => solution.rows(0, size).iter().copied().collect(),
+                false => {
+                    solve_with_ropes(
+                        &coefficient_matrix,
+                        &ForceVector::from_column_slice(impulses),
+                        &coupling_matrix,
+                        &ConstraintVector::zeros(coupling_count),
+                        &rope_matrix,
+                        &ConstraintVector::zeros(rope_count),
+                        &prescribed_vel_deltas,
+                    )
+                    .0
+                }
+            }
+        })
+        .collect()
+}
+
+fn dot(values1: &[f64], values2: &[f64]) -> f64 {
+    values1
+        .iter()
+        .zip(values2)
+        .map(|(value1, value2)| value1 * value2)
+        .sum()
+}
+
+/// Returns the random generalized impulses that the scene's random forces deliver over a tick,
+/// each drawn from a normal distribution whose variance is the noise intensity on the coordinate
+/// times the length of the tick.
+fn get_random_impulses(
+    frames: &[&FrameBox],
+    scene: &Scene,
+    states: &[State],
+    time: f64,
+    delta_time: f64,
+) -> Vec<f64> {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let id_index_map = get_id_index_map(frames);
+    let coordinate_offsets = get_coordinate_offsets(frames);
+    let mut impulses = vec![0.; states.len()];
+    for (random_force_index, random_force) in scene.random_forces.iter().enumerate() {
+        let frame_index = id_index_map[&random_force.frame_id];
+        let intensity = random_force.get_intensity(frames[frame_index].get_resistance());
+        for coordinate_index in coordinate_offsets[frame_index]..coordinate_offsets[frame_index + 1]
+        {
+            // Keying the noise on the state as well as the time keeps it fresh even if the
+            // caller doesn't advance the time:
+            let state = &states[coordinate_index];
+            let normal = random::get_normal(&[
+                scene.seed,
+                random_force_index as u64,
+                coordinate_index as u64,
+                time.to_bits(),
+                state.q.to_bits(),
+                state.qd.to_bits(),
+            ]);
+            impulses[coordinate_index] += (intensity * delta_time).sqrt() * normal;
+        }
+    }
+    impulses
+}
+
+/// Returns the pairs of indices of circles (given as centers and radii) whose bounding boxes
+/// overlap, found by sweeping across the circles from left to right.
+fn get_overlapping_pairs(circles: &[(Vec3, f64)]) -> Vec<(usize, usize)> {
+    let mut indices = (0..circles.len()).collect::<Vec<usize>>();
+    indices.sort_by(|index1, index2| {
+        let (pos1, radius1) = circles[*index1];
+        let (pos2, radius2) = circles[*index2];
+        (pos1[0] - radius1).total_cmp(&(pos2[0] - radius2))
+    });
+    let mut pairs = Vec::new();
+    for (sorted_index, index1) in indices.iter().enumerate() {
+        let (pos1, radius1) = circles[*index1];
+        for index2 in indices[sorted_index + 1..].iter() {
+            let (pos2, radius2) = circles[*index2];
+            if pos2[0] - radius2 > pos1[0] + radius1 {
+                break;
+            }
+            if (pos2[1] - pos1[1]).abs() <= radius1 + radius2 {
+                pairs.push((*index1, *index2));
+            }
+        }
+    }
+    pairs
+}
+
+/// Returns the contacts of weights that overlap the scene's obstacles, plus those of weights that
+/// overlap each other if the scene has collisions.
+fn get_contacts(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+) -> Vec<Contact> {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    if scene.obstacles.is_empty() && scene.collisions.is_none() {
+        return Vec::new();
+    }
+    let pos_mats = get_pos_mats(frames, index_path_map, states);
+    let inv_pos_mats = get_inv_pos_mats(&pos_mats);
+    let vel_mats = get_vel_mats(frames, index_path_map, &pos_mats, &inv_pos_mats, states);
+    let coordinate_frame_indices = get_coordinate_frame_indices(frames);
+    let weights = frames
+        .iter()
+        .enumerate()
+        .flat_map(|(frame_index, frame)| {
+            frame
+                .get_weights()
+                .iter()
+                .map(move |weight| (frame_index, weight))
+        })
+        .zip(get_weight_pos_vecs(frames, &pos_mats))
+        .map(|((frame_index, weight), pos)| (frame_index, weight, pos))
+        .collect::<Vec<_>>();
+    // The contact forces act at the edges of the weights, where friction also spins them:
+    let get_contact_pos_derivatives = |frame_index: FrameIndex, contact_pos: &Vec3| {
+        get_point_pos_derivatives(
+            frame_index,
+            contact_pos,
+            index_path_map,
+            &coordinate_frame_indices,
+            &vel_mats,
+        )
+    };
+    let get_contact = |pos_derivatives: &[Vec3], normal: &Vec3, depth, friction, restitution| {
+        let tangent = Vec3::new(-normal[1], normal[0], 0.);
+        Contact {
+            depth,
+            friction,
+            normal_derivatives: pos_derivatives
+                .iter()
+                .map(|pos_derivative| pos_derivative.dot(normal))
+                .collect(),
+            restitution,
+            tangent_derivatives: pos_derivatives
+                .iter()
+                .map(|pos_derivative| pos_derivative.dot(&tangent))
+                .collect(),
+        }
+    };
+    let mut contacts = Vec::new();
+    for (frame_index, weight, pos) in weights.iter() {
+        for obstacle in scene.obstacles.iter() {
+            if let Some((normal, depth)) = obstacle.shape.get_contact(pos, weight.radius) {
+                let pos_derivatives =
+                    get_contact_pos_derivatives(*frame_index, &(pos - normal * weight.radius));
+                contacts.push(get_contact(
+                    &pos_derivatives,
+                    &normal,
+                    depth,
+                    obstacle.friction,
+                    obstacle.restitution,
+                ));
+            }
+        }
+    }
+    if let Some(collisions) = &scene.collisions {
+        // Weights on the same frame never move apart, and weights on a frame and its parent that
+        // both cover the joint between them (e.g. the ends of two links of a chain) always
+        // overlap there.  Any other weights on a frame and its parent collide as usual:
+        let is_joined = |weight1: &(FrameIndex, &Weight, Vec3),
+                         weight2: &(FrameIndex, &Weight, Vec3)| {
+            let (frame_index1, weight1, pos1) = weight1;
+            let (frame_index2, weight2, pos2) = weight2;
+            let child_index = if frame_index1 == frame_index2 {
+                return true;
+            } else if get_parent_index(*frame_index1, index_path_map) == Some(*frame_index2) {
+                *frame_index1
+            } else if get_parent_index(*frame_index2, index_path_map) == Some(*frame_index1) {
+                *frame_index2
+            } else {
+                return false;
+            };
+            let joint_pos = pos_mats[child_index] * Vec3::new(0., 0., 1.);
+            (pos1 - joint_pos).norm() <= weight1.radius
+                && (pos2 - joint_pos).norm() <= weight2.radius
+        };
+        let colliding_weights = weights
+            .iter()
+            .filter(|(_, weight, _)| weight.radius > 0.)
+            .collect::<Vec<_>>();
+        let circles = colliding_weights
+            .iter()
+            .map(|(_, weight, pos)| (*pos, weight.radius))
+            .collect::<Vec<_>>();
+        for (index1, index2) in get_overlapping_pairs(&circles) {
+            let (frame_index1, weight1, pos1) = colliding_weights[index1];
+            let (frame_index2, weight2, pos2) = colliding_weights[index2];
+            let offset = pos2 - pos1;
+            let distance = offset.norm();
+            let depth = weight1.radius + weight2.radius - distance;
+            if depth <= 0.
+                || distance == 0.
+                || is_joined(colliding_weights[index1], colliding_weights[index2])
+            {
+                continue;
+            }
+            // The normal points from the first weight to the second, so the contact's normal
+            // speed is the rate at which the second weight's edge moves away from the first's:
+            let normal = offset / distance;
+            let pos_derivatives1 =
+                get_contact_pos_derivatives(*frame_index1, &(pos1 + normal * weight1.radius));
+            let pos_derivatives2 =
+                get_contact_pos_derivatives(*frame_index2, &(pos2 - normal * weight2.radius));
+            let pos_derivatives = pos_derivatives2
+                .iter()
+                .zip(pos_derivatives1)
+                .map(|(pos_derivative2, pos_derivative1)| pos_derivative2 - pos_derivative1)
+                .collect::<Vec<Vec3>>();
+            contacts.push(get_contact(
+                &pos_derivatives,
+                &normal,
+                depth,
+                collisions.friction,
+                collisions.restitution,
+            ));
+        }
+    }
+    contacts
+}
+
+/// Resolves contacts with sequential impulses: each contact's normal impulse stops its weight
+/// from approaching whatever it touches (or bounces it back off, if it hit fast enough), while
+/// friction impulses oppose sliding up to the Coulomb limit.  The coordinates are then nudged
+/// (again through the mass matrix) to undo any penetration, with the nudges solved for in the same
+/// way so that contacts sharing a body don't each push it out by their full depth.
+fn resolve_contacts_mut(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &mut [State],
+    contacts: &[Contact],
+    time: f64,
+) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    // The velocity changes caused by unit impulses along each contact's normal and tangent, and
+    // the resulting changes in the contact's own normal and tangential speeds.  Contacts that
+    // nothing can move (e.g. of weights on fixed frames) are left alone.
+    let impulse_sets = contacts
+        .iter()
+        .flat_map(|contact| {
+            vec![
+                contact.normal_derivatives.as_slice(),
+                contact.tangent_derivatives.as_slice(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let mut vel_deltas =
+        get_batch_impulse_vel_deltas(frames, index_path_map, scene, states, &impulse_sets, time)
+            .into_iter();
+    let responses = contacts
+        .iter()
+        .map(|contact| {
+            let normal_vel_deltas = vel_deltas.next().unwrap();
+            let tangent_vel_deltas = vel_deltas.next().unwrap();
+            let normal_compliance = dot(&contact.normal_derivatives, &normal_vel_deltas);
+            let tangent_compliance = dot(&contact.tangent_derivatives, &tangent_vel_deltas);
+            (
+                normal_vel_deltas,
+                normal_compliance,
+                tangent_vel_deltas,
+                tangent_compliance,
+            )
+        })
+        .collect::<Vec<_>>();
+    let mut qds = states.iter().map(|state| state.qd).collect::<Vec<f64>>();
+    let target_speeds = contacts
+        .iter()
+        .map(|contact| {
+            let speed = dot(&contact.normal_derivatives, &qds);
+            match speed < -CONTACT_RESTITUTION_THRESHOLD {
+                true => -contact.restitution * speed,
+                false => 0.,
+            }
+        })
+        .collect::<Vec<f64>>();
+    let mut normal_impulses = vec![0.; contacts.len()];
+    let mut tangent_impulses = vec![0.; contacts.len()];
+    let apply_impulse_mut = |qds: &mut [f64], impulse: f64, vel_deltas: &[f64]| {
+        for (qd, vel_delta) in qds.iter_mut().zip(vel_deltas) {
+            *qd += impulse * vel_delta;
+        }
+    };
+    for _ in 0..CONTACT_ITERATION_COUNT {
+        for (index, contact) in contacts.iter().enumerate() {
+            let (normal_vel_deltas, normal_compliance, tangent_vel_deltas, tangent_compliance) =
+                &responses[index];
+            if *normal_compliance < 1e-12 {
+                continue;
+            }
+            // Impulses accumulate over the passes, and only the total has to push rather than
+            // pull:
+            let speed = dot(&contact.normal_derivatives, &qds);
+            let normal_impulse = (normal_impulses[index]
+                + (target_speeds[index] - speed) / normal_compliance)
+                .max(0.);
+            apply_impulse_mut(
+                &mut qds,
+                normal_impulse - normal_impulses[index],
+                normal_vel_deltas,
+            );
+            normal_impulses[index] = normal_impulse;
+
+            if *tangent_compliance < 1e-12 {
+                continue;
+            }
+            let speed = dot(&contact.tangent_derivatives, &qds);
+            let max_impulse = contact.friction * normal_impulse;
+            let tangent_impulse = (tangent_impulses[index] - speed / tangent_compliance)
+                .max(-max_impulse)
+                .min(max_impulse);
+            apply_impulse_mut(
+                &mut qds,
+                tangent_impulse - tangent_impulses[index],
+                tangent_vel_deltas,
+            );
+            tangent_impulses[index] = tangent_impulse;
+        }
+    }
+    for (state, qd) in states.iter_mut().zip(qds) {
+        state.qd = qd;
+    }
+    let mut q_deltas = vec![0.; states.len()];
+    let mut corrections = vec![0.; contacts.len()];
+    for _ in 0..CONTACT_ITERATION_COUNT {
+        for (index, contact) in contacts.iter().enumerate() {
+            let (normal_vel_deltas, normal_compliance, _, _) = &responses[index];
+            if *normal_compliance < 1e-12 {
+                continue;
+            }
+            let depth = contact.depth - dot(&contact.normal_derivatives, &q_deltas);
+            let correction = (corrections[index] + depth / normal_compliance).max(0.);
+            apply_impulse_mut(
+                &mut q_deltas,
+                correction - corrections[index],
+                normal_vel_deltas,
+            );
+            corrections[index] = correction;
+        }
+    }
+    for (state, q_delta) in states.iter_mut().zip(q_deltas) {
+        state.q += q_delta;
+    }
+}
+
+/// Resolves contacts of the spring networks' particles with the obstacles, which works as for the
+/// frames' weights, but with each particle moving on its own.
+fn resolve_particle_contacts_mut(scene: &Scene, particle_states: &mut [State]) {
+    if scene.obstacles.is_empty() {
+        return;
+    }
+    let particle_pos_vel_vecs = get_particle_pos_vel_vecs(scene, particle_states);
+    let particles = scene
+        .spring_networks
+        .iter()
+        .flat_map(|spring_network| spring_network.particles.iter());
+    for ((particle, (pos, vel)), states) in particles
+        .zip(particle_pos_vel_vecs)
+        .zip(particle_states.chunks_mut(2))
+    {
+        let mut vel = vel;
+        for obstacle in scene.obstacles.iter() {
+            if let Some((normal, depth)) = obstacle.shape.get_contact(&pos, particle.radius) {
+                states[0].q += depth * normal[0];
+                states[1].q += depth * normal[1];
+                let speed = vel.dot(&normal);
+                if speed >= 0. {
+                    continue;
+                }
+                let normal_impulse = match speed < -CONTACT_RESTITUTION_THRESHOLD {
+                    true => -(1. + obstacle.restitution) * speed,
+                    false => -speed,
+                };
+                let tangent = Vec3::new(-normal[1], normal[0], 0.);
+                let max_impulse = obstacle.friction * normal_impulse;
+                let tangent_impulse = (-vel.dot(&tangent)).clamp(-max_impulse, max_impulse);
+                vel += normal_impulse * normal + tangent_impulse * tangent;
+            }
+        }
+        states[0].qd = vel[0];
+        states[1].qd = vel[1];
+    }
+}
+
+fn solve(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    external_forces: &[f64],
+    time: f64,
+) -> Vec<f64> {
+    solve_with_drive_forces(frames, index_path_map, scene, states, external_forces, time).0
+}
+
+/// Returns the number of states taken up by the spring networks' particles, which come after the
+/// frames' coordinates.
+fn get_particle_coordinate_count(scene: &Scene) -> usize {
+    scene
+        .spring_networks
+        .iter()
+        .map(|spring_network| 2 * spring_network.particles.len())
+        .sum()
+}
+
+/// Returns the world-space position and velocity of each of the spring networks' particles, in
+/// order.
+fn get_particle_pos_vel_vecs(scene: &Scene, particle_states: &[State]) -> Vec<(Vec3, Vec3)> {
+    debug_assert_eq!(particle_states.len(), get_particle_coordinate_count(scene));
+    scene
+        .spring_networks
+        .iter()
+        .flat_map(|spring_network| spring_network.particles.iter())
+        .zip(particle_states.chunks(2))
+        .map(|(particle, states)| {
+            let pos = particle.position.to_vec3() + Vec3::new(states[0].q, states[1].q, 0.);
+            (pos, Vec3::new(states[0].qd, states[1].qd, 0.))
+        })
+        .collect()
+}
+
+/// Returns the forces on the spring networks' particles, along with the generalized forces that
+/// their attachments exert on the frames' coordinates.  The springs only ever act between pairs
+/// of points, so the forces are summed spring by spring rather than through the frames' mass
+/// matrix.
+fn get_network_forces(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    particle_states: &[State],
+    time: f64,
+) -> (Vec<f64>, Vec<Vec3>) {
+    debug_assert_eq!(states.len(), get_coordinate_count(frames));
+    let mut generalized_forces = vec![0.; states.len()];
+    if scene.spring_networks.is_empty() {
+        return (generalized_forces, Vec::new());
+    }
+    let particle_pos_vel_vecs = get_particle_pos_vel_vecs(scene, particle_states);
+    let mut particle_force_vecs = scene
+        .spring_networks
+        .iter()
+        .flat_map(|spring_network| spring_network.particles.iter())
+        .zip(particle_pos_vel_vecs.iter())
+        .map(|(particle, (pos, vel))| {
+            get_weight_force_vec(scene, particle, particle.mass, pos, vel, time)
+        })
+        .collect::<Vec<Vec3>>();
+    let pos_mats = get_pos_mats(frames, index_path_map, states);
+    let inv_pos_mats = get_inv_pos_mats(&pos_mats);
+    let vel_mats = get_vel_mats(frames, index_path_map, &pos_mats, &inv_pos_mats, states);
+    let coordinate_frame_indices = get_coordinate_frame_indices(frames);
+    let id_index_map = get_id_index_map(frames);
+    let mut particle_offset = 0;
+    for spring_network in scene.spring_networks.iter() {
+        let particles = &spring_network.particles;
+        let pos_vel_vecs = &particle_pos_vel_vecs[particle_offset..][..particles.len()];
+        let force_vecs = &mut particle_force_vecs[particle_offset..][..particles.len()];
+        for ((index1, index2), rest_length) in spring_network
+            .springs
+            .iter()
+            .zip(spring_network.rest_lengths.iter())
+        {
+            let (pos1, vel1) = &pos_vel_vecs[*index1];
+            let (pos2, vel2) = &pos_vel_vecs[*index2];
+            let offset = pos2 - pos1;
+            let distance = offset.norm();
+            if distance == 0. {
+                continue;
+            }
+            let direction = offset / distance;
+            let tension = spring_network.stiffness * (distance - rest_length)
+                + spring_network.damping * (vel2 - vel1).dot(&direction);
+            force_vecs[*index1] += tension * direction;
+            force_vecs[*index2] -= tension * direction;
+        }
+        for attachment in spring_network.attachments.iter() {
+            let frame_index = id_index_map[&attachment.frame_id];
+            let weight = &frames[frame_index].get_weights()[attachment.weight_index];
+            let weight_pos = pos_mats[frame_index] * weight.position.to_vec3();
+            let pos_derivatives = get_point_pos_derivatives(
+                frame_index,
+                &weight_pos,
+                index_path_map,
+                &coordinate_frame_indices,
+                &vel_mats,
+            );
+            let weight_vel = pos_derivatives
+                .iter()
+                .zip(states)
+                .map(|(pos_derivative, state)| pos_derivative * state.qd)
+                .sum::<Vec3>();
+            let (pos, vel) = &pos_vel_vecs[attachment.particle_index];
+            let force_vec = spring_network.stiffness * (weight_pos - pos)
+                + spring_network.damping * (weight_vel - vel);
+            force_vecs[attachment.particle_index] += force_vec;
+            for (generalized_force, pos_derivative) in
+                generalized_forces.iter_mut().zip(pos_derivatives)
+            {
+                *generalized_force -= pos_derivative.dot(&force_vec);
+            }
+        }
+        particle_offset += particles.len();
+    }
+    (generalized_forces, particle_force_vecs)
+}
+
+/// Returns the accelerations of the frames' coordinates, followed by those of the spring
+/// networks' particles (which take the forces after the frames' ones in `external_forces`).
+fn solve_with_networks(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &[State],
+    external_forces: &[f64],
+    time: f64,
+) -> Vec<f64> {
+    let count = get_coordinate_count(frames);
+    debug_assert_eq!(states.len(), count + get_particle_coordinate_count(scene));
+    if scene.spring_networks.is_empty() {
+        return solve(frames, index_path_map, scene, states, external_forces, time);
+    }
+    let (states, particle_states) = states.split_at(count);
+    let (external_forces, particle_external_forces) = external_forces.split_at(count);
+    let (attachment_forces, particle_force_vecs) =
+        get_network_forces(frames, index_path_map, scene, states, particle_states, time);
+    let external_forces = external_forces
+        .iter()
+        .zip(attachment_forces)
+        .map(|(external_force, attachment_force)| external_force + attachment_force)
+        .collect::<Vec<f64>>();
+    let mut qdds = solve(
+        frames,
+        index_path_map,
+        scene,
+        states,
+        &external_forces,
+        time,
+    );
+    qdds.extend(
+        scene
+            .spring_networks
+            .iter()
+            .flat_map(|spring_network| spring_network.particles.iter())
+            .zip(particle_force_vecs)
+            .zip(particle_external_forces.chunks(2))
+            .flat_map(
+                |((particle, force_vec), external_forces)| match particle.mass > 0. {
+                    true => vec![
+                        (force_vec[0] + external_forces[0]) / particle.mass,
+                        (force_vec[1] + external_forces[1]) / particle.mass,
+                    ],
+                    false => vec![0., 0.],
+                },
+            ),
+    );
+    qdds
+}
+
+fn tick_simple_mut(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &mut [State],
+    external_forces: &[f64],
+    time: f64,
+    delta_time: f64,
+) {
+    let apply_deltas_mut = |states: &mut [State], qdd_vec: &[f64], delta_time: f64| {
+        states.iter_mut().enumerate().for_each(|(index, state)| {
+            state.q += state.qd * delta_time;
+            state.qd += qdd_vec[index] * delta_time;
+        });
+    };
+    let qdd_vec = solve_with_networks(frames, index_path_map, scene, states, external_forces, time);
+    apply_deltas_mut(states, &qdd_vec, delta_time);
+}
+
+fn tick_runge_kutta_mut(
+    frames: &[&FrameBox],
+    index_path_map: &FrameIndexPathMap,
+    scene: &Scene,
+    states: &mut [State],
+    external_forces: &[f64],
+    time: f64,
+    delta_time: f64,
+) {
+    let count = states.len();
+    let get_qs = |states: &[State]| states.iter().map(|state| state.q).collect::<Vec<f64>>();
+    let get_qds = |states: &[State]| states.iter().map(|state| state.qd).collect::<Vec<f64>>();
+    let unzip_states = |states: &[State]| (get_qs(states), get_qds(states));
+    let zip_states = |qs: &[f64], qds: &[f64]| {
+        qs.iter()
+            .zip(qds)
+            .map(|(q, qd)| State { q: *q, qd: *qd })
+            .collect::<Vec<State>>()
+    };
+    let solve = |qs: &[f64], qds: &[f64], time: f64| {
+        solve_with_networks(
+            frames,
+            index_path_map,
+            scene,
+            &zip_states(qs, qds),
+            external_forces,
+            time,
+        )
+    };
+    let apply_deltas =
+        |qs: &[f64], qds: &[f64], delta_qs: &[f64], delta_qds: &[f64], delta_time: f64| {
+            let mut new_qs = Vec::<f64>::new();
+            let mut new_qds = Vec::<f64>::new();
+            new_qs.reserve(count);
+            new_qds.reserve(count);
+            for i in 0..count {
+                new_qs.push(qs[i] + delta_qs[i] * delta_time);
+                new_qds.push(qds[i] + delta_qds[i] * delta_time);
+            }
+            (new_qs, new_qds)
+        };
+
+    let (qs0, qds0) = unzip_states(states);
+    let qdds0 = solve(&qs0, &qds0, time);
+
+    let (qs1, qds1) = apply_deltas(&qs0, &qds0, &qds0, &qdds0, delta_time / 2.);
+    let qdds1 = solve(&qs1, &qds1, time + delta_time / 2.);
+
+    let (qs2, qds2) = apply_deltas(&qs0, &qds0, &qds1, &qdds1, delta_time / 2.);
+    let qdds2 = solve(&qs2, &qds2, time + delta_time / 2.);
 
     let (qs3, qds3) = apply_deltas(&qs0, &qds0, &qds2, &qdds2, delta_time);
-    let qdds3 = solve(&qs3, &qds3);
+    let qdds3 = solve(&qs3, &qds3, time + delta_time);
 
     let mut qds = Vec::<f64>::new();
     let mut qdds = Vec::<f64>::new();
@@ -558,35 +2075,235 @@ fn tick_runge_kutta_mut(
 impl Solver {
     pub fn new(scene: Scene) -> Self {
         Self {
-            scene: scene,
+            scene,
             runge_kutta: true,
         }
     }
 
-    pub fn tick_mut(&self, states: &mut [State], external_forces: &[f64], delta_time: f64) -> () {
+    /// Returns the number of generalized coordinates (and so states) in the scene, ordered by
+    /// frame in depth-first order and followed by the `[x, y]` of each spring network particle.
+    pub fn get_coordinate_count(&self) -> usize {
+        get_coordinate_count(&sort_frames(&self.scene.frames))
+            + get_particle_coordinate_count(&self.scene)
+    }
+
+    pub fn tick_mut(
+        &self,
+        states: &mut [State],
+        external_forces: &[f64],
+        time: f64,
+        delta_time: f64,
+    ) {
         let frames = sort_frames(&self.scene.frames);
-        assert_eq!(states.len(), frames.len());
-        assert_eq!(external_forces.len(), frames.len());
+        let count = get_coordinate_count(&frames);
+        assert_eq!(
+            states.len(),
+            count + get_particle_coordinate_count(&self.scene)
+        );
+        assert_eq!(external_forces.len(), states.len());
         let index_path_map = get_index_path_map(&frames);
+        let random_impulses =
+            get_random_impulses(&frames, &self.scene, &states[..count], time, delta_time);
         if self.runge_kutta {
             tick_runge_kutta_mut(
                 &frames,
                 &index_path_map,
-                &self.scene.gravity,
+                &self.scene,
                 states,
                 external_forces,
+                time,
                 delta_time,
             );
         } else {
             tick_simple_mut(
                 &frames,
                 &index_path_map,
-                &self.scene.gravity,
+                &self.scene,
                 states,
                 external_forces,
+                time,
                 delta_time,
             );
         }
+        // Everything else only involves the frames:
+        let (states, particle_states) = states.split_at_mut(count);
+        set_prescribed_states_mut(&frames, &self.scene, states, time + delta_time);
+        // The random forces are integrated as kicks at the end of each tick (i.e. the
+        // Euler-Maruyama scheme), with the resistance that balances them handled as usual above:
+        if !self.scene.random_forces.is_empty() {
+            let vel_deltas = get_impulse_vel_deltas(
+                &frames,
+                &index_path_map,
+                &self.scene,
+                states,
+                &random_impulses,
+                time + delta_time,
+            );
+            for (state, vel_delta) in states.iter_mut().zip(vel_deltas) {
+                state.qd += vel_delta;
+            }
+        }
+        let contacts = get_contacts(&frames, &index_path_map, &self.scene, states);
+        resolve_contacts_mut(
+            &frames,
+            &index_path_map,
+            &self.scene,
+            states,
+            &contacts,
+            time + delta_time,
+        );
+        resolve_particle_contacts_mut(&self.scene, particle_states);
+    }
+
+    /// Returns the generalized force needed to drive each of the scene's prescribed motions, in
+    /// the order that they're declared.
+    pub fn get_drive_forces(
+        &self,
+        states: &[State],
+        external_forces: &[f64],
+        time: f64,
+    ) -> Vec<f64> {
+        let frames = sort_frames(&self.scene.frames);
+        let count = get_coordinate_count(&frames);
+        assert_eq!(states.len(), self.get_coordinate_count());
+        assert_eq!(external_forces.len(), states.len());
+        let index_path_map = get_index_path_map(&frames);
+        let id_coordinate_map = get_id_coordinate_map(&frames);
+        let (states, particle_states) = states.split_at(count);
+        let (attachment_forces, _) = get_network_forces(
+            &frames,
+            &index_path_map,
+            &self.scene,
+            states,
+            particle_states,
+            time,
+        );
+        let external_forces = external_forces
+            .iter()
+            .zip(attachment_forces)
+            .map(|(external_force, attachment_force)| external_force + attachment_force)
+            .collect::<Vec<f64>>();
+        let (_, drive_forces) = solve_with_drive_forces(
+            &frames,
+            &index_path_map,
+            &self.scene,
+            states,
+            &external_forces,
+            time,
+        );
+        self.scene
+            .prescribed_motions
+            .iter()
+            .map(|prescribed_motion| drive_forces[id_coordinate_map[&prescribed_motion.frame_id]])
+            .collect()
+    }
+
+    /// Returns the generalized forces equivalent to the given world-space point forces, to be
+    /// added to the external forces passed to `tick_mut`.  Point forces are converted at the
+    /// given states, so their lever arms are held fixed over the following tick.
+    pub fn get_point_forces(
+        &self,
+        states: &[State],
+        point_forces: &[PointForce],
+    ) -> Result<Vec<f64>, Error> {
+        let frames = sort_frames(&self.scene.frames);
+        let count = get_coordinate_count(&frames);
+        assert_eq!(states.len(), self.get_coordinate_count());
+        let id_index_map = get_id_index_map(&frames);
+        if let Some(point_force) = point_forces
+            .iter()
+            .find(|point_force| !id_index_map.contains_key(&point_force.frame_id))
+        {
+            return Err(Error(format!("Unknown frame id: {}", point_force.frame_id)));
+        }
+        let index_path_map = get_index_path_map(&frames);
+        let mut generalized_forces =
+            get_point_generalized_forces(&frames, &index_path_map, &states[..count], point_forces);
+        // Point forces only act on frames, not on the particles:
+        generalized_forces.resize(states.len(), 0.);
+        Ok(generalized_forces)
+    }
+
+    /// Applies generalized impulses (one per coordinate) to the states' velocities, against the
+    /// masses at the given time.
+    pub fn apply_impulses_mut(&self, states: &mut [State], impulses: &[f64], time: f64) {
+        let frames = sort_frames(&self.scene.frames);
+        let count = get_coordinate_count(&frames);
+        assert_eq!(states.len(), self.get_coordinate_count());
+        assert_eq!(impulses.len(), states.len());
+        let index_path_map = get_index_path_map(&frames);
+        let (states, particle_states) = states.split_at_mut(count);
+        let (impulses, particle_impulses) = impulses.split_at(count);
+        let vel_deltas = get_impulse_vel_deltas(
+            &frames,
+            &index_path_map,
+            &self.scene,
+            states,
+            impulses,
+            time,
+        );
+        for (state, vel_delta) in states.iter_mut().zip(vel_deltas) {
+            state.qd += vel_delta;
+        }
+        let particles = self
+            .scene
+            .spring_networks
+            .iter()
+            .flat_map(|spring_network| spring_network.particles.iter())
+            .flat_map(|particle| vec![particle, particle]);
+        for ((state, impulse), particle) in particle_states
+            .iter_mut()
+            .zip(particle_impulses)
+            .zip(particles)
+        {
+            if particle.mass > 0. {
+                state.qd += impulse / particle.mass;
+            }
+        }
+    }
+
+    /// Applies world-space impulses at points on frames to the states' velocities, given as
+    /// `PointForce`s whose `force` is the impulse.
+    pub fn apply_point_impulses_mut(
+        &self,
+        states: &mut [State],
+        point_impulses: &[PointForce],
+        time: f64,
+    ) -> Result<(), Error> {
+        let impulses = self.get_point_forces(states, point_impulses)?;
+        self.apply_impulses_mut(states, &impulses, time);
+        Ok(())
+    }
+
+    /// Converts a world-space position into the coordinate system of the given frame, e.g. to
+    /// find where on a body the mouse grabbed it.
+    pub fn get_local_position(
+        &self,
+        states: &[State],
+        frame_id: &FrameId,
+        position: &Position,
+    ) -> Result<Position, Error> {
+        let frames = sort_frames(&self.scene.frames);
+        let count = get_coordinate_count(&frames);
+        assert_eq!(states.len(), self.get_coordinate_count());
+        let frame_index = *get_id_index_map(&frames)
+            .get(frame_id)
+            .ok_or_else(|| Error(format!("Unknown frame id: {}", frame_id)))?;
+        let index_path_map = get_index_path_map(&frames);
+        let pos_mats = get_pos_mats(&frames, &index_path_map, &states[..count]);
+        let local_pos = pos_mats[frame_index].try_inverse().unwrap() * position.to_vec3();
+        Ok(Position([local_pos[0], local_pos[1]]))
+    }
+
+    /// Returns the world-space positions of the spring networks' particles, in order, e.g. for
+    /// drawing them.
+    pub fn get_particle_positions(&self, states: &[State]) -> Vec<Position> {
+        let count = get_coordinate_count(&sort_frames(&self.scene.frames));
+        assert_eq!(states.len(), self.get_coordinate_count());
+        get_particle_pos_vel_vecs(&self.scene, &states[count..])
+            .iter()
+            .map(|(pos, _)| Position([pos[0], pos[1]]))
+            .collect()
     }
 }
 
@@ -594,11 +2311,42 @@ impl Solver {
 mod tests {
     use std::f64::consts::PI;
 
+    use crate::Actuator;
+    use crate::Attachment;
+    use crate::Backlash;
+    use crate::Collisions;
+    use crate::Coupling;
+    use crate::Curve;
+    use crate::CurveFrame;
+    use crate::Damping;
+    use crate::Deadband;
+    use crate::Field;
+    use crate::FixedFrame;
+    use crate::Fluid;
+    use crate::ForceProfile;
+    use crate::ForceSchedule;
+    use crate::FreeFrame;
+    use crate::Interaction;
+    use crate::InteractionLaw;
+    use crate::MassSchedule;
+    use crate::MotionProfile;
+    use crate::Obstacle;
+    use crate::ObstacleShape;
+    use crate::PointForce;
     use crate::Position;
+    use crate::PrescribedMotion;
+    use crate::RandomForce;
+    use crate::Rope;
+    use crate::RopePoint;
     use crate::RotationalFrame;
     use crate::Scene;
+    use crate::Shape;
+    use crate::SlidingPivotFrame;
+    use crate::SpringNetwork;
     use crate::TrackFrame;
     use crate::Weight;
+    use crate::WheelFrame;
+    use crate::Wind;
 
     use super::*;
 
@@ -653,6 +2401,12 @@ mod tests {
         vec![cart, ball]
     }
 
+    fn get_sample_scene() -> Scene {
+        get_sample_frames()
+            .into_iter()
+            .fold(Scene::new(), |scene, frame| scene.add_frame(frame))
+    }
+
     #[test]
     fn test_sort_frames() {
         assert_eq!(
@@ -687,7 +2441,7 @@ mod tests {
         let local_pos_mats: Vec<Mat3> = frames
             .iter()
             .zip(states.iter())
-            .map(|(frame, state)| frame.get_local_pos_matrix(state.q))
+            .map(|(frame, state)| frame.get_local_pos_matrix(&[state.q]))
             .collect();
         assert_eq!(pos_mats.len(), frames.len());
         assert_eq!(pos_mats[BALL_INDEX], local_pos_mats[BALL_INDEX]);
@@ -715,7 +2469,7 @@ mod tests {
         let local_pos_mats: Vec<Mat3> = frames
             .iter()
             .zip(states.iter())
-            .map(|(frame, state)| frame.get_local_pos_matrix(state.q))
+            .map(|(frame, state)| frame.get_local_pos_matrix(&[state.q]))
             .collect();
         assert_eq!(inv_pos_mats.len(), frames.len());
         assert_abs_diff_eq!(
@@ -755,7 +2509,7 @@ mod tests {
         let local_vel_mats: Vec<Mat3> = frames
             .iter()
             .zip(states.iter())
-            .map(|(frame, state)| frame.get_local_vel_matrix(state.q))
+            .map(|(frame, state)| frame.get_local_vel_matrix(&[state.q], 0))
             .collect();
         assert_eq!(vel_mats.len(), frames.len());
         assert_abs_diff_eq!(
@@ -792,7 +2546,9 @@ mod tests {
         let local_accel_mats: Vec<Mat3> = frames
             .iter()
             .zip(states.iter())
-            .map(|(frame, state)| frame.get_local_accel_matrix(state.q))
+            .map(|(frame, state)| {
+                state.qd * state.qd * frame.get_local_accel_matrix(&[state.q], 0, 0)
+            })
             .collect();
         assert_eq!(accel_mats.len(), frames.len());
         assert_abs_diff_eq!(
@@ -877,19 +2633,13 @@ mod tests {
         );
         let qds: Vec<f64> = states.iter().map(|state| state.qd).collect();
         assert_eq!(vel_sum_mats.len(), frames.len());
-        assert_abs_diff_eq!(
-            accel_sum_mats[BALL_INDEX],
-            qds[BALL_INDEX] * qds[BALL_INDEX] * accel_mats[BALL_INDEX]
-        );
-        assert_abs_diff_eq!(
-            accel_sum_mats[CART_INDEX],
-            qds[CART_INDEX] * qds[CART_INDEX] * accel_mats[CART_INDEX]
-        );
+        assert_abs_diff_eq!(accel_sum_mats[BALL_INDEX], accel_mats[BALL_INDEX]);
+        assert_abs_diff_eq!(accel_sum_mats[CART_INDEX], accel_mats[CART_INDEX]);
         assert_abs_diff_eq!(
             accel_sum_mats[PENDULUM1_INDEX],
             accel_sum_mats[CART_INDEX]
                 + 2. * qds[PENDULUM1_INDEX] * vel_sum_mats[CART_INDEX] * vel_mats[PENDULUM1_INDEX]
-                + qds[PENDULUM1_INDEX] * qds[PENDULUM1_INDEX] * accel_mats[PENDULUM1_INDEX]
+                + accel_mats[PENDULUM1_INDEX]
         );
         assert_abs_diff_eq!(
             accel_sum_mats[PENDULUM2_INDEX],
@@ -897,7 +2647,7 @@ mod tests {
                 + 2. * qds[PENDULUM2_INDEX]
                     * vel_sum_mats[PENDULUM1_INDEX]
                     * vel_mats[PENDULUM2_INDEX]
-                + qds[PENDULUM2_INDEX] * qds[PENDULUM2_INDEX] * accel_mats[PENDULUM2_INDEX],
+                + accel_mats[PENDULUM2_INDEX],
             epsilon = 1e-8
         );
     }
@@ -979,7 +2729,9 @@ mod tests {
         let vel_mats =
             super::get_vel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, &states);
         let weight_offsets = super::get_weight_offsets(&frames);
+        let layout = super::FrameLayout::new(&frames, &index_path_map);
         let weight_pos_vecs = super::get_weight_pos_vecs(&frames, &pos_mats);
+        let weight_masses = super::get_weight_masses(&frames, &Scene::new(), 0.);
         let get_mass = |frame_index: usize, weight_index: usize| {
             frames[frame_index].get_weights()[weight_index].mass
         };
@@ -989,11 +2741,10 @@ mod tests {
             super::get_coefficient_matrix_entry(
                 row_index,
                 col_index,
-                &frames,
-                &index_path_map,
+                &layout,
                 &vel_mats,
-                &weight_offsets,
                 &weight_pos_vecs,
+                &weight_masses,
             )
         };
 
@@ -1048,24 +2799,19 @@ mod tests {
         let inv_pos_mats = super::get_inv_pos_mats(&pos_mats);
         let vel_mats =
             super::get_vel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, &states);
-        let weight_offsets = super::get_weight_offsets(&frames);
         let weight_pos_vecs = super::get_weight_pos_vecs(&frames, &pos_mats);
-        let coefficient_matrix = super::get_coefficient_matrix(
-            &frames,
-            &index_path_map,
-            &vel_mats,
-            &weight_offsets,
-            &weight_pos_vecs,
-        );
+        let layout = super::FrameLayout::new(&frames, &index_path_map);
+        let weight_masses = super::get_weight_masses(&frames, &Scene::new(), 0.);
+        let coefficient_matrix =
+            super::get_coefficient_matrix(&layout, &vel_mats, &weight_pos_vecs, &weight_masses);
         let get_coefficient = |row_index, col_index| {
             super::get_coefficient_matrix_entry(
                 row_index,
                 col_index,
-                &frames,
-                &index_path_map,
+                &layout,
                 &vel_mats,
-                &weight_offsets,
                 &weight_pos_vecs,
+                &weight_masses,
             )
         };
         assert_eq!(coefficient_matrix.shape(), (frames.len(), frames.len()));
@@ -1080,20 +2826,531 @@ mod tests {
     }
 
     #[test]
-    fn test_get_force_vector_entry() {
+    fn test_get_weight_force_vecs() {
+        let ball = TrackFrame::new(BALL_ID.into()).add_weight(
+            Weight::new(2.)
+                .set_drag(0.5)
+                .set_quadratic_drag(0.25)
+                .set_position(Position([0., 2.])),
+        );
+        let wind = Wind::new(Vec3::new(5., 0., 0.)).set_shear(Vec3::new(1., 0., 0.));
+        let scene = Scene::new().add_frame(Box::new(ball)).set_wind(wind);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 3. }];
+        let pos_mats = super::get_pos_mats(&frames, &index_path_map, &states);
+        let inv_pos_mats = super::get_inv_pos_mats(&pos_mats);
+        let vel_mats =
+            super::get_vel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, &states);
+        let vel_sum_mats =
+            super::get_vel_sum_mats(&frames, &index_path_map, &pos_mats, &vel_mats, &states);
+        let weight_pos_vecs = super::get_weight_pos_vecs(&frames, &pos_mats);
+        let weight_masses = super::get_weight_masses(&frames, &scene, 0.);
+        let weight_force_vecs = super::get_weight_force_vecs(
+            &frames,
+            &scene,
+            &vel_sum_mats,
+            &weight_pos_vecs,
+            &weight_masses,
+            0.,
+        );
+        // The ball moves at 3 through air moving at 5 + 2 (due to shear), so the drag pushes it
+        // forwards at a relative speed of 4:
+        assert_abs_diff_eq!(
+            weight_force_vecs[0],
+            Vec3::new(0.5 * 4. + 0.25 * 4. * 4., -20., 0.)
+        );
+    }
+
+    #[test]
+    fn test_drag_terminal_velocity() {
+        // A weight falling down a vertical track with quadratic drag:
+        let ball = TrackFrame::new(BALL_ID.into())
+            .set_angle(-PI / 2.)
+            .add_weight(Weight::new(2.).set_quadratic_drag(0.5));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(ball)));
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..600 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].qd, (2. * 10. / 0.5_f64).sqrt(), epsilon = 1e-6);
+
+        // A weight on a horizontal track is carried along by the wind:
+        let ball = TrackFrame::new(BALL_ID.into())
+            .add_weight(Weight::new(2.).set_drag(1.).set_quadratic_drag(0.5));
+        let wind = Wind::new(Vec3::new(3., 0., 0.));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(ball)).set_wind(wind));
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..1800 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].qd, 3., epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_fields() {
+        // A bead on a radial spoke of a spinning platform is flung outwards:
+        let bead = TrackFrame::new(BALL_ID.into()).add_weight(Weight::new(2.));
+        let field = Field::Rotating {
+            angular_velocity: 3.,
+            center: Vec3::zeros(),
+        };
+        let scene = Scene::new()
+            .set_gravity(Vec3::zeros())
+            .add_frame(Box::new(bead))
+            .add_field(field);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 2., qd: 0. }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0.], 0.);
+        assert_abs_diff_eq!(qdds[0], 3. * 3. * 2., epsilon = 1e-8);
+
+        // A pendulum hanging towards a point mass on a tilted uniform gravity:
+        let pendulum = RotationalFrame::new(PENDULUM1_ID.into())
+            .add_weight(Weight::new(2.).set_position(Position([3., 0.])));
+        let field = Field::PointMass {
+            position: Vec3::new(3., 4., 0.),
+            softening: 0.,
+            strength: 32.,
+        };
+        let scene = Scene::new()
+            .set_gravity(Vec3::new(1., 0., 0.))
+            .add_frame(Box::new(pendulum))
+            .add_field(field);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 0. }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0.], 0.);
+        assert_abs_diff_eq!(
+            qdds[0],
+            2. * 32. / 16. * 3. / (2. * 3. * 3.),
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn test_force_schedules() {
+        // A sinusoidal force shakes a cart on a horizontal track, which drifts along at an average
+        // velocity of `A / (2 * pi * f * m)`:
+        let cart = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(2.));
+        let force_schedule = ForceSchedule::new(
+            CART_ID.into(),
+            ForceProfile::Sinusoid {
+                amplitude: 3.,
+                frequency: 0.5,
+                phase: 0.,
+            },
+        );
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(cart))
+                .add_force_schedule(force_schedule),
+        );
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..90 {
+            let time = tick_index as f64 / 60.;
+            solver.tick_mut(&mut states, &[0.], time, 1. / 60.);
+        }
+        let omega = 2. * PI * 0.5;
+        let time = 1.5;
+        assert_abs_diff_eq!(
+            states[0].qd,
+            3. / (omega * 2.) * (1. - (omega * time).cos()),
+            epsilon = 1e-6
+        );
+        assert_abs_diff_eq!(
+            states[0].q,
+            3. / (omega * 2.) * (time - (omega * time).sin() / omega),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_prescribed_motion() {
+        // A cart accelerating uniformly along its track swings back the pendulum hanging from it:
+        let cart = TrackFrame::new(CART_ID.into())
+            .add_weight(Weight::new(2.))
+            .add_child(Box::new(
+                RotationalFrame::new(PENDULUM1_ID.into())
+                    .add_weight(Weight::new(1.).set_position(Position([0., -2.]))),
+            ));
+        let prescribed_motion = PrescribedMotion::new(
+            CART_ID.into(),
+            MotionProfile::Polynomial {
+                coefficients: vec![0., 0., 0.75],
+            },
+        );
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(cart))
+                .add_prescribed_motion(prescribed_motion),
+        );
+        let frames = super::sort_frames(&solver.scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 0. }];
+        let qdds = super::solve(
+            &frames,
+            &index_path_map,
+            &solver.scene,
+            &states,
+            &[0., 0.],
+            0.,
+        );
+        assert_abs_diff_eq!(qdds[0], 1.5, epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], -0.75, epsilon = 1e-8);
+
+        // The drive pushes both masses along, minus the pendulum's swing back:
+        let drive_forces = solver.get_drive_forces(&states, &[0., 0.], 0.);
+        assert_eq!(drive_forces.len(), 1);
+        assert_abs_diff_eq!(drive_forces[0], 3. * 1.5 - 2. * 0.75, epsilon = 1e-8);
+
+        // Pushing on the cart has no effect on its motion:
+        for tick_index in 0..60 {
+            solver.tick_mut(&mut states, &[5., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].q, 0.75, epsilon = 1e-12);
+        assert_abs_diff_eq!(states[0].qd, 1.5, epsilon = 1e-12);
+        assert!(states[1].q < -0.1);
+    }
+
+    #[test]
+    fn test_prescribed_motion_coupling() {
+        // A coupling follows along with a prescribed driver frame:
+        let scene = Scene::new()
+            .add_frame(Box::new(
+                TrackFrame::new(CART_ID.into()).add_weight(Weight::new(2.)),
+            ))
+            .add_frame(Box::new(
+                TrackFrame::new(BALL_ID.into()).add_weight(Weight::new(1.)),
+            ))
+            .add_coupling(Coupling::new(BALL_ID.into(), CART_ID.into()).set_ratio(2.))
+            .add_prescribed_motion(PrescribedMotion::new(
+                CART_ID.into(),
+                MotionProfile::Polynomial {
+                    coefficients: vec![0., 0., 0.75],
+                },
+            ));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 0. }];
+        let (qdds, drive_forces) = super::solve_with_drive_forces(
+            &frames,
+            &index_path_map,
+            &scene,
+            &states,
+            &[0., 0.],
+            0.,
+        );
+        let cart_index = frames
+            .iter()
+            .position(|frame| frame.get_id() == CART_ID)
+            .unwrap();
+        let ball_index = 1 - cart_index;
+        assert_abs_diff_eq!(qdds[cart_index], 1.5, epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[ball_index], 3., epsilon = 1e-8);
+        // The drive also has to push the ball along, through the coupling's 2:1 leverage:
+        assert_abs_diff_eq!(drive_forces[cart_index], 2. * 1.5 + 2. * 3., epsilon = 1e-8);
+        assert_abs_diff_eq!(drive_forces[ball_index], 0., epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_fixed_frame() {
+        // A pendulum hanging from a rotated static base swings like one with its angle offset:
+        let base = FixedFrame::new("base".into())
+            .set_angle(PI / 2.)
+            .set_position(Position([1., 2.]))
+            .add_child(Box::new(
+                RotationalFrame::new(PENDULUM1_ID.into())
+                    .add_weight(Weight::new(2.).set_position(Position([3., 0.]))),
+            ));
+        let scene = Scene::new().add_frame(Box::new(base));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State {
+            q: -PI / 2. - 0.4,
+            qd: 1.,
+        }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0.], 0.);
+        assert_eq!(qdds.len(), 1);
+        assert_abs_diff_eq!(qdds[0], -10. * (-0.4_f64).cos() / 3., epsilon = 1e-8);
+
+        // A bracket carries its weight along with a cart, without a degree of freedom of its own:
+        let cart = TrackFrame::new(CART_ID.into())
+            .add_weight(Weight::new(2.))
+            .add_child(Box::new(
+                FixedFrame::new("bracket".into())
+                    .set_position(Position([0., -1.]))
+                    .add_weight(Weight::new(3.).set_position(Position([1., 0.]))),
+            ));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(cart)));
+        assert_eq!(solver.get_coordinate_count(), 1);
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..60 {
+            solver.tick_mut(&mut states, &[10.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].q, 1., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[0].qd, 2., epsilon = 1e-8);
+
+        // A scene of nothing but fixed frames has no state at all:
+        let base = FixedFrame::new("base".into())
+            .add_weight(Weight::new(1.))
+            .add_child(Box::new(
+                FixedFrame::new("bracket".into()).add_weight(Weight::new(2.)),
+            ));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(base)));
+        assert_eq!(solver.get_coordinate_count(), 0);
+        let mut states = Vec::new();
+        solver.tick_mut(&mut states, &[], 0., 1. / 60.);
+        assert!(states.is_empty());
+    }
+
+    #[test]
+    fn test_curve_frame() {
+        // A bead on a circular wire swings like a pendulum, with the arc length as its coordinate:
+        let curve = Curve::Arc {
+            center: Position([0., 0.]),
+            radius: 2.,
+            start_angle: -PI / 2.,
+        };
+        let bead = CurveFrame::new(BALL_ID.into(), curve).add_weight(Weight::new(3.));
+        let scene = Scene::new().add_frame(Box::new(bead));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0.6, qd: 1.5 }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0.], 0.);
+        assert_abs_diff_eq!(qdds[0], -10. * (0.6_f64 / 2.).sin(), epsilon = 1e-8);
+
+        // A spline through collinear points behaves like a straight, inclined track:
+        let curve = Curve::new_spline(vec![
+            Position([0., 0.]),
+            Position([3., -4.]),
+            Position([6., -8.]),
+        ]);
+        let bead = CurveFrame::new(BALL_ID.into(), curve).add_weight(Weight::new(3.));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(bead)));
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..60 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        // Gravity accelerates the parameter by `10 * 4 / 5^2` along the 5-unit-long segments:
+        assert_abs_diff_eq!(states[0].qd, 1.6, epsilon = 1e-8);
+        assert_abs_diff_eq!(states[0].q, 0.8, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_track_frame_rotation() {
+        // A telescoping pendulum: the track points down the rod and turns with it.
+        let pendulum = RotationalFrame::new(PENDULUM1_ID.into()).add_child(Box::new(
+            TrackFrame::new(BALL_ID.into())
+                .set_angle(-PI / 2.)
+                .add_weight(Weight::new(2.)),
+        ));
+        let scene = Scene::new().add_frame(Box::new(pendulum));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let (angle, angle_vel, length, length_vel) = (0.4_f64, 1.5, 2., -0.5);
+        let states = vec![
+            State {
+                q: angle,
+                qd: angle_vel,
+            },
+            State {
+                q: length,
+                qd: length_vel,
+            },
+        ];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0., 0.], 0.);
+        assert_abs_diff_eq!(
+            qdds[0],
+            (-10. * angle.sin() - 2. * length_vel * angle_vel) / length,
+            epsilon = 1e-8
+        );
+        assert_abs_diff_eq!(
+            qdds[1],
+            length * angle_vel * angle_vel + 10. * angle.cos(),
+            epsilon = 1e-8
+        );
+
+        // A bead on a rod spun at a constant rate is flung outwards along the rod:
+        let rod = RotationalFrame::new(PENDULUM1_ID.into()).add_child(Box::new(
+            TrackFrame::new(BALL_ID.into()).add_weight(Weight::new(2.)),
+        ));
+        let prescribed_motion = PrescribedMotion::new(
+            PENDULUM1_ID.into(),
+            MotionProfile::Polynomial {
+                coefficients: vec![0., 3.],
+            },
+        );
+        let solver = Solver::new(
+            Scene::new()
+                .set_gravity(Vec3::zeros())
+                .add_frame(Box::new(rod))
+                .add_prescribed_motion(prescribed_motion),
+        );
+        let mut states = vec![State { q: 0., qd: 3. }, State { q: 0.5, qd: 0. }];
+        for tick_index in 0..600 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 600., 1. / 600.);
+        }
+        assert_abs_diff_eq!(states[0].q, 3., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[1].q, 0.5 * 3_f64.cosh(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_free_frame() {
+        // A thrown, spinning body's center of mass follows a parabola while its spin is preserved:
+        let body = FreeFrame::new(BALL_ID.into())
+            .add_weight(Weight::new(1.).set_position(Position([1., 0.])))
+            .add_weight(Weight::new(3.).set_position(Position([-1., 0.])));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(body)));
+        assert_eq!(solver.get_coordinate_count(), 3);
+        let get_center = |states: &[State]| {
+            let (sin, cos) = states[2].q.sin_cos();
+            [states[0].q - 0.5 * cos, states[1].q - 0.5 * sin]
+        };
+        let mut states = vec![
+            State { q: 0., qd: 2. },
+            State { q: 0., qd: 5. },
+            State { q: 0., qd: 3. },
+        ];
+        let [x0, y0] = get_center(&states);
+        // The center of mass starts at `(-0.5, 0)`, so the spin adds `(0, -1.5)` to its velocity:
+        let (vx, vy) = (2., 5. - 1.5);
+        for tick_index in 0..600 {
+            solver.tick_mut(
+                &mut states,
+                &[0., 0., 0.],
+                tick_index as f64 / 600.,
+                1. / 600.,
+            );
+        }
+        let [x, y] = get_center(&states);
+        assert_abs_diff_eq!(x, x0 + vx, epsilon = 1e-6);
+        assert_abs_diff_eq!(y, y0 + vy - 5., epsilon = 1e-6);
+        assert_abs_diff_eq!(states[2].q, 3., epsilon = 1e-6);
+        assert_abs_diff_eq!(states[2].qd, 3., epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_free_frame_point_mass() {
+        // A projectile with no rotational inertia follows a parabola, and its angle (which moves
+        // nothing) keeps turning at whatever rate it was given:
+        let ball = FreeFrame::new(BALL_ID.into()).add_weight(Weight::new(1.));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(ball)));
+        let mut states = vec![
+            State { q: 0., qd: 2. },
+            State { q: 0., qd: 5. },
+            State { q: 0., qd: 1. },
+        ];
+        for tick_index in 0..60 {
+            solver.tick_mut(
+                &mut states,
+                &[0., 0., 0.],
+                tick_index as f64 / 60.,
+                1. / 60.,
+            );
+        }
+        assert_abs_diff_eq!(states[0].q, 2., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[1].q, 5. - 5., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[2].q, 1., epsilon = 1e-8);
+
+        // Impulses move it as well, without spinning it:
+        solver.apply_impulses_mut(&mut states, &[1., 0., 1.], 1.);
+        assert_abs_diff_eq!(states[0].qd, 3., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[2].qd, 1., epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_sliding_pivot_frame() {
+        // Matches a cart with a pendulum, where the pendulum's weight hangs from the cart's origin:
+        let trolley = SlidingPivotFrame::new(CART_ID.into())
+            .add_weight(Weight::new(3.).set_position(Position([0., -2.])));
+        let cart = TrackFrame::new(CART_ID.into()).add_child(Box::new(
+            RotationalFrame::new(PENDULUM1_ID.into())
+                .add_weight(Weight::new(3.).set_position(Position([0., -2.]))),
+        ));
+        let states = vec![State { q: 0.5, qd: 1. }, State { q: 0.3, qd: -2. }];
+        let get_qdds = |frame: FrameBox| {
+            let scene = Scene::new().add_frame(frame);
+            let frames = super::sort_frames(&scene.frames);
+            let index_path_map = super::get_index_path_map(&frames);
+            super::solve(&frames, &index_path_map, &scene, &states, &[1., 0.], 0.)
+        };
+        let expected_qdds = get_qdds(Box::new(cart));
+        let qdds = get_qdds(Box::new(trolley));
+        assert_eq!(qdds.len(), 2);
+        assert_abs_diff_eq!(qdds[0], expected_qdds[0], epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], expected_qdds[1], epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_wheel_frame() {
+        // A uniform disc rolls down a slope at two thirds of a sliding block's acceleration:
+        let slope = 0.3_f64;
+        let wheel = WheelFrame::new(BALL_ID.into(), 0.5)
+            .set_angle(-slope)
+            .add_weight(
+                Shape::Disc {
+                    mass: 2.,
+                    position: Position([0., 0.]),
+                    radius: 0.5,
+                }
+                .to_weight(),
+            );
+        let solver = Solver::new(Scene::new().add_frame(Box::new(wheel)));
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..60 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        // Rolling downhill (forwards along the ground) turns the wheel clockwise:
+        let accel = 2. / 3. * 10. * slope.sin();
+        assert_abs_diff_eq!(-0.5 * states[0].qd, accel, epsilon = 1e-8);
+        assert_abs_diff_eq!(-0.5 * states[0].q, accel / 2., epsilon = 1e-8);
+
+        // A body balanced upright on the axle stays put, while a tilted one falls forwards and
+        // drives the wheel backwards underneath it:
+        let get_qdds = |body_angle: f64| {
+            let wheel = WheelFrame::new(CART_ID.into(), 0.5)
+                .add_weight(Weight::new(1.).set_inertia(0.125))
+                .add_child(Box::new(
+                    RotationalFrame::new(PENDULUM1_ID.into())
+                        .add_weight(Weight::new(4.).set_position(Position([0., 1.]))),
+                ));
+            let scene = Scene::new().add_frame(Box::new(wheel));
+            let frames = super::sort_frames(&scene.frames);
+            let index_path_map = super::get_index_path_map(&frames);
+            let states = vec![
+                State { q: 0., qd: 0. },
+                State {
+                    q: body_angle,
+                    qd: 0.,
+                },
+            ];
+            super::solve(&frames, &index_path_map, &scene, &states, &[0., 0.], 0.)
+        };
+        let qdds = get_qdds(0.);
+        assert_abs_diff_eq!(qdds[0], 0., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], 0., epsilon = 1e-8);
+        let qdds = get_qdds(-0.1);
+        assert!(qdds[0] > 0.);
+        // The body's absolute angle is the sum of the wheel's and its own:
+        assert!(qdds[0] + qdds[1] < 0.);
+    }
+
+    #[test]
+    fn test_get_force_vector() {
         let frames = get_sample_frames();
         let frames = super::sort_frames(&frames);
         let index_path_map = super::get_index_path_map(&frames);
-        let weight_offsets = super::get_weight_offsets(&frames);
+        let layout = super::FrameLayout::new(&frames, &index_path_map);
         let get_entry = |row_index, states: &[State], gravity: &Vec3, external_forces: &[f64]| {
-            let pos_mats = super::get_pos_mats(&frames, &index_path_map, &states);
+            let pos_mats = super::get_pos_mats(&frames, &index_path_map, states);
             let inv_pos_mats = super::get_inv_pos_mats(&pos_mats);
             let vel_mats =
-                super::get_vel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, &states);
+                super::get_vel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, states);
             let vel_sum_mats =
-                super::get_vel_sum_mats(&frames, &index_path_map, &pos_mats, &vel_mats, &states);
+                super::get_vel_sum_mats(&frames, &index_path_map, &pos_mats, &vel_mats, states);
             let accel_mats =
-                super::get_accel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, &states);
+                super::get_accel_mats(&frames, &index_path_map, &pos_mats, &inv_pos_mats, states);
             let accel_sum_mats = super::get_accel_sum_mats(
                 &frames,
                 &index_path_map,
@@ -1101,33 +3358,39 @@ mod tests {
                 &vel_mats,
                 &accel_mats,
                 &vel_sum_mats,
-                &states,
+                states,
             );
             let weight_pos_vecs = super::get_weight_pos_vecs(&frames, &pos_mats);
-            super::get_force_vector_entry(
-                row_index,
-                &frames,
-                &index_path_map,
-                &vel_mats,
+            let scene = Scene::new().set_gravity(*gravity);
+            let weight_masses = super::get_weight_masses(&frames, &scene, 0.);
+            let weight_force_vecs = super::get_weight_force_vecs(
+                &frames,
+                &scene,
                 &vel_sum_mats,
+                &weight_pos_vecs,
+                &weight_masses,
+                0.,
+            );
+            super::get_force_vector(
+                &layout,
+                &vel_mats,
                 &accel_sum_mats,
-                &weight_offsets,
                 &weight_pos_vecs,
-                &gravity,
-                &states,
-                &external_forces,
-            )
+                &weight_masses,
+                &weight_force_vecs,
+                external_forces,
+            )[row_index]
         };
 
         let states = get_sample_states();
         let gravity = Vec3::new(0., -10., 0.);
-        let ext_forces: Vec<f64> = iter::repeat(2.).take(frames.len()).collect();
+        let ext_forces: Vec<f64> = iter::repeat_n(2., frames.len()).collect();
         let zero_states: Vec<State> = states
             .iter()
             .map(|state| State { q: state.q, qd: 0. })
             .collect();
         let zero_gravity = Vec3::new(0., 0., 0.);
-        let zero_ext_forces: Vec<f64> = iter::repeat(0.).take(frames.len()).collect();
+        let zero_ext_forces: Vec<f64> = iter::repeat_n(0., frames.len()).collect();
         for frame_index in 0..frames.len() {
             let entry = get_entry(frame_index, &zero_states, &zero_gravity, &zero_ext_forces);
             // Absence of momentum, gravity, and external forces implies no net force:
@@ -1167,17 +3430,17 @@ mod tests {
     #[test]
     fn test_get_system_of_equations() {
         let states = get_sample_states();
-        let frames = get_sample_frames();
-        let frames = super::sort_frames(&frames);
+        let scene = get_sample_scene();
+        let frames = super::sort_frames(&scene.frames);
         let index_path_map = super::get_index_path_map(&frames);
-        let gravity = Vec3::new(0., -10., 0.);
-        let ext_forces: Vec<f64> = iter::repeat(2.).take(frames.len()).collect();
+        let ext_forces: Vec<f64> = iter::repeat_n(2., frames.len()).collect();
         let (coeff_matrix, force_vector) = super::get_system_of_equations(
             &frames,
             &index_path_map,
-            &gravity,
+            &scene,
             &states,
             &ext_forces,
+            0.,
         );
         let frame_count = frames.len();
         assert_eq!(coeff_matrix.shape(), (frame_count, frame_count));
@@ -1188,18 +3451,18 @@ mod tests {
     fn test_tick_simple_mut() {
         let states1 = get_sample_states();
         let mut states2 = states1.clone();
-        let frames = get_sample_frames();
-        let frames = super::sort_frames(&frames);
+        let scene = get_sample_scene();
+        let frames = super::sort_frames(&scene.frames);
         let index_path_map = super::get_index_path_map(&frames);
-        let gravity = Vec3::new(0., -10., 0.);
-        let ext_forces: Vec<f64> = iter::repeat(2.).take(frames.len()).collect();
+        let ext_forces: Vec<f64> = iter::repeat_n(2., frames.len()).collect();
         let delta_time = 1. / 60.;
         super::tick_simple_mut(
             &frames,
             &index_path_map,
-            &gravity,
+            &scene,
             &mut states2,
             &ext_forces,
+            0.,
             delta_time,
         );
 
@@ -1225,18 +3488,18 @@ mod tests {
     fn test_tick_runge_kutta_mut() {
         let states1 = get_sample_states();
         let mut states2 = states1.clone();
-        let frames = get_sample_frames();
-        let frames = super::sort_frames(&frames);
+        let scene = get_sample_scene();
+        let frames = super::sort_frames(&scene.frames);
         let index_path_map = super::get_index_path_map(&frames);
-        let gravity = Vec3::new(0., -10., 0.);
-        let ext_forces: Vec<f64> = iter::repeat(2.).take(frames.len()).collect();
+        let ext_forces: Vec<f64> = iter::repeat_n(2., frames.len()).collect();
         let delta_time = 1. / 60.;
         super::tick_runge_kutta_mut(
             &frames,
             &index_path_map,
-            &gravity,
+            &scene,
             &mut states2,
             &ext_forces,
+            0.,
             delta_time,
         );
 
@@ -1266,7 +3529,7 @@ mod tests {
         }
         let mut solver = Solver::new(scene);
         let frames = super::sort_frames(&solver.scene.frames);
-        let ext_forces: Vec<f64> = iter::repeat(2.).take(frames.len()).collect();
+        let ext_forces: Vec<f64> = iter::repeat_n(2., frames.len()).collect();
         let mut state_history1: Vec<Vec<State>> = Vec::new();
         let mut state_history2: Vec<Vec<State>> = Vec::new();
         let max_time_index = 50;
@@ -1275,17 +3538,17 @@ mod tests {
         println!("Simulating with runge_kutta=false...");
         solver.runge_kutta = false;
         let mut states = get_sample_states();
-        for time_index in 0..max_time_index {
+        for _ in 0..max_time_index {
             state_history1.push(states.clone());
-            solver.tick_mut(&mut states, &ext_forces, delta_time);
+            solver.tick_mut(&mut states, &ext_forces, 0., delta_time);
         }
 
         println!("Simulating with runge_kutta=true...");
         solver.runge_kutta = true;
         let mut states = get_sample_states();
-        for time_index in 0..max_time_index {
+        for _ in 0..max_time_index {
             state_history2.push(states.clone());
-            solver.tick_mut(&mut states, &ext_forces, delta_time);
+            solver.tick_mut(&mut states, &ext_forces, 0., delta_time);
         }
 
         for time_index in 0..max_time_index {
@@ -1304,9 +3567,1052 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_actuator() {
+        let cart = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(2.));
+        let actuator = Actuator::new(CART_ID.into(), 3., 10.)
+            .set_gear_ratio(2.)
+            .set_rotor_inertia(0.5);
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(cart))
+                .add_actuator(actuator),
+        );
+        let frames = super::sort_frames(&solver.scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+
+        // The command is saturated, and the rotor inertia is reflected through the gearbox:
+        let states = vec![State { q: 0., qd: 0. }];
+        let qdds = super::solve(&frames, &index_path_map, &solver.scene, &states, &[7.], 0.);
+        assert_abs_diff_eq!(qdds[0], 2. * 3. / (2. + 2. * 2. * 0.5));
+
+        // The cart approaches but never exceeds the geared-down no-load speed:
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for _ in 0..1800 {
+            solver.tick_mut(&mut states, &[1.], 0., 1. / 60.);
+            assert!(states[0].qd <= 5. + 1e-8);
+        }
+        assert_abs_diff_eq!(states[0].qd, 5., epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_coupling() {
+        // A rack driven by a pinion whose weights are balanced about its axle:
+        let pinion = RotationalFrame::new(PENDULUM1_ID.into())
+            .add_weight(Weight::new(2.).set_position(Position([0.5, 0.])))
+            .add_weight(Weight::new(2.).set_position(Position([-0.5, 0.])));
+        let rack = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(3.));
+        let coupling = Coupling::new(CART_ID.into(), PENDULUM1_ID.into())
+            .set_ratio(-0.5)
+            .set_offset(1.);
+        let scene = Scene::new()
+            .add_frame(Box::new(rack))
+            .add_frame(Box::new(pinion))
+            .add_coupling(coupling);
+        let solver = Solver::new(scene);
+        let frames = super::sort_frames(&solver.scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| frame.get_id().as_str())
+                .collect::<Vec<_>>(),
+            [PENDULUM1_ID, CART_ID]
+        );
+
+        // Pushing the rack accelerates it against its own mass plus the pinion's reflected
+        // inertia:
+        let states = vec![State { q: 0., qd: 0. }, State { q: 1., qd: 0. }];
+        let qdds = super::solve(
+            &frames,
+            &index_path_map,
+            &solver.scene,
+            &states,
+            &[0., 4.],
+            0.,
+        );
+        let pinion_inertia = 2. * 2. * 0.5 * 0.5;
+        let rack_qdd = 4. / (3. + pinion_inertia / (0.5 * 0.5));
+        assert_abs_diff_eq!(qdds[1], rack_qdd, epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[0], rack_qdd / -0.5, epsilon = 1e-8);
+
+        // The coupling holds over time, and an initial violation is pulled back into place:
+        let mut states = vec![State { q: 0.1, qd: 0. }, State { q: 1., qd: 0. }];
+        for _ in 0..300 {
+            solver.tick_mut(&mut states, &[0., 4.], 0., 1. / 60.);
+        }
+        assert!(states[1].q > 5.);
+        assert_abs_diff_eq!(
+            solver.scene.couplings[0].get_error(states[1].q, states[0].q),
+            0.,
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_coupling_backlash() {
+        // Two equal carts geared together with some play, where the driving cart starts moving
+        // and the other one starts at rest:
+        let coupling =
+            Coupling::new(BALL_ID.into(), CART_ID.into()).set_backlash(Backlash::new(0.2, 100.));
+        let scene = Scene::new()
+            .add_frame(Box::new(
+                TrackFrame::new(CART_ID.into()).add_weight(Weight::new(1.)),
+            ))
+            .add_frame(Box::new(
+                TrackFrame::new(BALL_ID.into()).add_weight(Weight::new(1.)),
+            ))
+            .add_coupling(coupling);
+        let solver = Solver::new(scene);
+        let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 1. }];
+
+        // The driven cart stays put until the driver has taken up the slack:
+        for tick_index in 0..5 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_eq!(states[0].q, 0.);
+        assert_eq!(states[0].qd, 0.);
+
+        // Then the elastic engagement hands all of the driver's velocity over:
+        for tick_index in 5..27 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].qd, 1., epsilon = 0.05);
+        assert_abs_diff_eq!(states[1].qd, 0., epsilon = 0.05);
+        assert_abs_diff_eq!(states[0].qd + states[1].qd, 1., epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_deadband() {
+        let get_solver = |damping| {
+            let backlash = Backlash::new(0.2, 100.).set_damping(damping);
+            let scene = Scene::new()
+                .add_frame(Box::new(
+                    TrackFrame::new(CART_ID.into()).add_weight(Weight::new(1.)),
+                ))
+                .add_deadband(Deadband::new(CART_ID.into(), backlash).set_position(1.));
+            Solver::new(scene)
+        };
+
+        // A cart rattles back and forth in a loose joint, moving freely within the gap and
+        // bouncing off of the stiff ends of it:
+        let solver = get_solver(0.);
+        let mut states = vec![State { q: 1., qd: 1. }];
+        let mut max_error: f64 = 0.;
+        for tick_index in 0..300 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+            max_error = max_error.max((states[0].q - 1.).abs());
+            if (states[0].q - 1.).abs() < 0.1 {
+                assert_abs_diff_eq!(states[0].qd.abs(), 1., epsilon = 0.05);
+            }
+        }
+        // Engaging at unit speed overshoots the gap by about `v / omega`:
+        assert_abs_diff_eq!(max_error, 0.1 + 0.1, epsilon = 0.01);
+
+        // With damping, the impacts soak up the cart's energy:
+        let solver = get_solver(10.);
+        let mut states = vec![State { q: 1., qd: 1. }];
+        for tick_index in 0..300 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert!(states[0].qd.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_spring_network() {
+        // A particle hanging from a fixed peg by its attachment, and another hanging below it by
+        // a spring, settle where the springs hold up the weight below them:
+        let peg = FixedFrame::new("peg".into()).add_weight(Weight::new(1.));
+        let network = SpringNetwork::new(100.)
+            .set_damping(5.)
+            .add_particle(Weight::new(1.))
+            .add_particle(Weight::new(1.).set_position(Position([0., -1.])))
+            .add_spring(0, 1)
+            .add_attachment(Attachment::new("peg".into(), 0, 0));
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(peg))
+                .add_spring_network(network),
+        );
+        assert_eq!(solver.get_coordinate_count(), 4);
+        let mut states = vec![State { q: 0., qd: 0. }; 4];
+        for tick_index in 0..600 {
+            solver.tick_mut(&mut states, &[0.; 4], tick_index as f64 / 60., 1. / 60.);
+        }
+        let positions = solver.get_particle_positions(&states);
+        assert_abs_diff_eq!(positions[0].0[0], 0., epsilon = 1e-6);
+        assert_abs_diff_eq!(positions[0].0[1], -0.2, epsilon = 1e-4);
+        assert_abs_diff_eq!(positions[1].0[1], -1.3, epsilon = 1e-4);
+
+        // External forces and impulses on the particles come after the frames' ones:
+        let mut states = vec![State { q: 0., qd: 0. }; 4];
+        solver.apply_impulses_mut(&mut states, &[0., 0., 2., 0.], 0.);
+        assert_eq!(states[3].qd, 0.);
+        assert_eq!(states[2].qd, 2.);
+    }
+
+    #[test]
+    fn test_spring_network_flag() {
+        // A cloth flag hanging from a pendulum's rod by its leading edge, with the rod's
+        // weights at the flag's corners:
+        let pendulum = RotationalFrame::new(PENDULUM1_ID.into())
+            .add_weight(Weight::new(1.).set_position(Position([0., -1.])))
+            .add_weight(Weight::new(1.).set_position(Position([0., -2.])));
+        let flag =
+            SpringNetwork::new_grid(500., Position([0., -1.]), 3, 3, 0.5, || Weight::new(0.05))
+                .set_damping(0.5)
+                .add_attachment(Attachment::new(PENDULUM1_ID.into(), 0, 0))
+                .add_attachment(Attachment::new(PENDULUM1_ID.into(), 1, 6));
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(pendulum))
+                .add_spring_network(flag),
+        );
+        let mut states = vec![State { q: 0., qd: 0. }; 19];
+        states[0].qd = 1.;
+        for tick_index in 0..600 {
+            solver.tick_mut(&mut states, &[0.; 19], tick_index as f64 / 600., 1. / 600.);
+        }
+
+        // The flag swings along with the pendulum, and its weight and drag slow the pendulum
+        // down, compared with one swinging on its own:
+        let angle = states[0].q;
+        let positions = solver.get_particle_positions(&states);
+        assert_abs_diff_eq!(positions[0].0[0], angle.sin(), epsilon = 0.05);
+        assert_abs_diff_eq!(positions[0].0[1], -angle.cos(), epsilon = 0.05);
+        assert_abs_diff_eq!(positions[6].0[0], 2. * angle.sin(), epsilon = 0.05);
+        assert_abs_diff_eq!(positions[6].0[1], -2. * angle.cos(), epsilon = 0.05);
+        let bare_pendulum = RotationalFrame::new(PENDULUM1_ID.into())
+            .add_weight(Weight::new(1.).set_position(Position([0., -1.])))
+            .add_weight(Weight::new(1.).set_position(Position([0., -2.])));
+        let bare_solver = Solver::new(Scene::new().add_frame(Box::new(bare_pendulum)));
+        let mut bare_states = vec![State { q: 0., qd: 1. }];
+        for tick_index in 0..600 {
+            bare_solver.tick_mut(&mut bare_states, &[0.], tick_index as f64 / 600., 1. / 600.);
+        }
+        assert!((angle - bare_states[0].q).abs() > 0.05);
+    }
+
+    #[test]
+    fn test_spring_network_bumper() {
+        // A cart with a soft bumper (a square of springs, whose back edge is attached to the
+        // cart's weights) rolls into a wall without gravity:
+        let get_solver = |bumper| {
+            let cart = TrackFrame::new(CART_ID.into())
+                .add_weight(Weight::new(0.5).set_position(Position([0.5, 0.25])))
+                .add_weight(Weight::new(0.5).set_position(Position([0.5, -0.25])));
+            let wall = Obstacle::new(ObstacleShape::HalfPlane {
+                normal: Position([-1., 0.]),
+                position: Position([2., 0.]),
+            });
+            let scene = Scene::new()
+                .set_gravity(Vec3::zeros())
+                .add_frame(Box::new(cart))
+                .add_obstacle(wall);
+            Solver::new(match bumper {
+                true => scene.add_spring_network(
+                    SpringNetwork::new_grid(100., Position([0.5, 0.25]), 2, 2, 0.5, || {
+                        Weight::new(0.05).set_radius(0.05)
+                    })
+                    .set_damping(0.1)
+                    .add_attachment(Attachment::new(CART_ID.into(), 0, 0))
+                    .add_attachment(Attachment::new(CART_ID.into(), 1, 2)),
+                ),
+                false => scene,
+            })
+        };
+
+        // The bumper squashes against the wall and pushes the cart back before it gets there:
+        let solver = get_solver(true);
+        let mut states = vec![State { q: 0., qd: 0. }; 9];
+        states[0].qd = 1.;
+        let mut max_q: f64 = 0.;
+        for tick_index in 0..300 {
+            solver.tick_mut(&mut states, &[0.; 9], tick_index as f64 / 60., 1. / 60.);
+            max_q = max_q.max(states[0].q);
+        }
+        assert!(max_q > 1.);
+        assert!(max_q < 1.4);
+        assert!(states[0].qd < -0.5);
+
+        // Without it, the cart doesn't touch the wall until its own weights reach it:
+        let solver = get_solver(false);
+        let mut states = vec![State { q: 0., qd: 1. }];
+        for tick_index in 0..90 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert!(states[0].q > 1.4);
+    }
+
+    #[test]
+    fn test_fluid_buoy() {
+        // A buoy displacing twice its mass of water, dropped in on a vertical track, settles
+        // half underwater with its center at the surface:
+        let buoy = TrackFrame::new(BALL_ID.into())
+            .set_angle(PI / 2.)
+            .add_weight(Weight::new(1.).set_radius(0.1).set_volume(0.002));
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(buoy))
+                .set_fluid(Fluid::new(1000.).set_drag(5.)),
+        );
+        let mut states = vec![State { q: 1., qd: 0. }];
+        for tick_index in 0..600 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].q, 0., epsilon = 1e-3);
+        assert_abs_diff_eq!(states[0].qd, 0., epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_fluid_ship_roll() {
+        // A catamaran with ballast below its deck, heeled over and floating freely:
+        let get_hull = |x| {
+            Weight::new(1.)
+                .set_position(Position([x, 0.]))
+                .set_radius(0.5)
+                .set_volume(0.003)
+        };
+        let ship = FreeFrame::new(CART_ID.into())
+            .add_weight(get_hull(-1.))
+            .add_weight(get_hull(1.))
+            .add_weight(Weight::new(1.).set_position(Position([0., -0.3])));
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(ship))
+                .set_fluid(Fluid::new(1000.).set_drag(2.)),
+        );
+        let mut states = vec![
+            State { q: 0., qd: 0. },
+            State { q: 0., qd: 0. },
+            State { q: 0.3, qd: 0. },
+        ];
+
+        // The deeper hull's extra buoyancy rights the ship, which rolls back and forth less and
+        // less, and ends up level with each hull half underwater:
+        let mut roll_signs = Vec::new();
+        for tick_index in 0..1200 {
+            solver.tick_mut(&mut states, &[0.; 3], tick_index as f64 / 60., 1. / 60.);
+            roll_signs.push(states[2].q > 0.);
+        }
+        assert!(roll_signs.windows(2).any(|pair| pair[0] != pair[1]));
+        assert_abs_diff_eq!(states[2].q, 0., epsilon = 1e-3);
+        assert_abs_diff_eq!(states[1].q, 0., epsilon = 1e-3);
+    }
+
+    fn get_atwood_machine_scene(length: f64) -> Scene {
+        // Two weights hanging on vertical tracks from either end of a rope over two pulleys:
+        let left = TrackFrame::new(CART_ID.into())
+            .set_angle(PI / 2.)
+            .set_position(Position([-1., -2.]))
+            .add_weight(Weight::new(3.));
+        let right = TrackFrame::new(BALL_ID.into())
+            .set_angle(PI / 2.)
+            .set_position(Position([1., -2.]))
+            .add_weight(Weight::new(1.));
+        let rope = Rope::new(length)
+            .add_point(RopePoint::new(Position([0., 0.])).set_frame_id(CART_ID.into()))
+            .add_point(RopePoint::new(Position([-1., 0.])))
+            .add_point(RopePoint::new(Position([1., 0.])))
+            .add_point(RopePoint::new(Position([0., 0.])).set_frame_id(BALL_ID.into()));
+        Scene::new()
+            .add_frame(Box::new(right))
+            .add_frame(Box::new(left))
+            .add_rope(rope)
+    }
+
+    #[test]
+    fn test_rope() {
+        let scene = get_atwood_machine_scene(6.);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        assert_eq!(frames[0].get_id(), CART_ID);
+        let states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 0. }];
+        let solve = |scene: &Scene, states: &[State], external_forces: &[f64]| {
+            super::solve(&frames, &index_path_map, scene, states, external_forces, 0.)
+        };
+
+        // The heavier weight descends at `g * (m1 - m2) / (m1 + m2)`:
+        let qdds = solve(&scene, &states, &[0., 0.]);
+        assert_abs_diff_eq!(qdds[0], -5., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], 5., epsilon = 1e-8);
+
+        // The rope can't push, so yanking the lighter weight upwards slackens it:
+        let qdds = solve(&scene, &states, &[0., 50.]);
+        assert_abs_diff_eq!(qdds[0], -10., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], 40., epsilon = 1e-8);
+
+        // A rope that's longer than the path through its points is slack, so both weights fall:
+        let scene = get_atwood_machine_scene(7.);
+        let qdds = solve(&scene, &states, &[0., 0.]);
+        assert_abs_diff_eq!(qdds[0], -10., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], -10., epsilon = 1e-8);
+
+        // The rope's length holds over time:
+        let solver = Solver::new(get_atwood_machine_scene(6.));
+        let mut states = states;
+        for tick_index in 0..30 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].q, -0.625, epsilon = 1e-6);
+        assert_abs_diff_eq!(states[1].q, 0.625, epsilon = 1e-6);
+
+        // A body swinging down on a rope tied to the origin pulls it taut with the centripetal
+        // force, and accelerates like a pendulum bob:
+        let body = FreeFrame::new(BALL_ID.into()).add_weight(Weight::new(2.).set_inertia(1.));
+        let rope = Rope::new(2.)
+            .add_point(RopePoint::new(Position([0., 0.])))
+            .add_point(RopePoint::new(Position([0., 0.])).set_frame_id(BALL_ID.into()));
+        let scene = Scene::new().add_frame(Box::new(body)).add_rope(rope);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![
+            State { q: 2., qd: 0. },
+            State { q: 0., qd: -3. },
+            State { q: 0., qd: 0. },
+        ];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0., 0., 0.], 0.);
+        assert_abs_diff_eq!(qdds[0], -3. * 3. / 2., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], -10., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[2], 0., epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_interactions() {
+        // Two weights on horizontal tracks attract each other, next to a heavy fixed weight:
+        let get_qdds = |interaction: Interaction| {
+            let left = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(2.));
+            let right = TrackFrame::new(BALL_ID.into())
+                .set_position(Position([2., 0.]))
+                .add_weight(Weight::new(3.));
+            let magnet = FixedFrame::new("magnet".into())
+                .set_position(Position([1., 1.]))
+                .add_weight(Weight::new(100.));
+            let scene = Scene::new()
+                .set_gravity(Vec3::zeros())
+                .add_frame(Box::new(magnet))
+                .add_frame(Box::new(right))
+                .add_frame(Box::new(left))
+                .add_interaction(interaction);
+            let frames = super::sort_frames(&scene.frames);
+            let index_path_map = super::get_index_path_map(&frames);
+            assert_eq!(frames[0].get_id(), CART_ID);
+            let states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 0. }];
+            super::solve(&frames, &index_path_map, &scene, &states, &[0., 0.], 0.)
+        };
+
+        let law = || InteractionLaw::Gravitation { constant: 4. };
+        let qdds = get_qdds(
+            Interaction::new(law())
+                .add_frame_id(CART_ID.into())
+                .add_frame_id(BALL_ID.into()),
+        );
+        let force = 4. * 2. * 3. / (2. * 2.);
+        assert_abs_diff_eq!(qdds[0], force / 2., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], -force / 3., epsilon = 1e-8);
+
+        // Without any frames listed, the fixed weight pulls on both as well:
+        let qdds = get_qdds(Interaction::new(law()));
+        let magnet_accel = 4. * 100. / 2. / 2_f64.sqrt();
+        assert_abs_diff_eq!(qdds[0], force / 2. + magnet_accel, epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], -force / 3. - magnet_accel, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_point_forces() {
+        // Pushing a pendulum's bob sideways pushes the cart and swings the pendulum:
+        let cart = TrackFrame::new(CART_ID.into())
+            .add_weight(Weight::new(2.))
+            .add_child(Box::new(
+                RotationalFrame::new(PENDULUM1_ID.into())
+                    .add_weight(Weight::new(1.).set_position(Position([0., -2.]))),
+            ));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(cart)));
+        let states = vec![State { q: 5., qd: 1. }, State { q: 0., qd: 0. }];
+        let point_force = PointForce::new(PENDULUM1_ID.into(), Vec3::new(3., 0., 0.))
+            .set_position(Position([0., -2.]));
+        let forces = solver.get_point_forces(&states, &[point_force]).unwrap();
+        assert_abs_diff_eq!(forces[0], 3., epsilon = 1e-12);
+        assert_abs_diff_eq!(forces[1], 6., epsilon = 1e-12);
+
+        // Forces on the cart itself don't affect the pendulum, and multiple forces add up:
+        let point_forces = [
+            PointForce::new(CART_ID.into(), Vec3::new(1., 5., 0.)),
+            PointForce::new(PENDULUM1_ID.into(), Vec3::new(0., -4., 0.))
+                .set_position(Position([1., 0.])),
+        ];
+        let forces = solver.get_point_forces(&states, &point_forces).unwrap();
+        assert_abs_diff_eq!(forces[0], 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(forces[1], -4., epsilon = 1e-12);
+
+        // A grabbed point is found in the frame's own coordinate system:
+        let states = vec![State { q: 5., qd: 0. }, State { q: PI / 2., qd: 0. }];
+        let local_position = solver
+            .get_local_position(&states, &PENDULUM1_ID.into(), &Position([7., 0.]))
+            .unwrap();
+        assert_abs_diff_eq!(local_position.0[0], 0., epsilon = 1e-12);
+        assert_abs_diff_eq!(local_position.0[1], -2., epsilon = 1e-12);
+
+        let point_force = PointForce::new("missing".into(), Vec3::zeros());
+        assert_eq!(
+            solver
+                .get_point_forces(&states, &[point_force])
+                .unwrap_err()
+                .to_string(),
+            "Unknown frame id: missing"
+        );
+    }
+
+    #[test]
+    fn test_impulses() {
+        // Kicking a free body off its center of mass sets it both moving and spinning:
+        let body = FreeFrame::new(BALL_ID.into()).add_weight(Weight::new(2.).set_inertia(1.));
+        let solver = Solver::new(Scene::new().add_frame(Box::new(body)));
+        let mut states = vec![
+            State { q: 0., qd: 1. },
+            State { q: 0., qd: 0. },
+            State { q: 0., qd: 0. },
+        ];
+        let point_impulse =
+            PointForce::new(BALL_ID.into(), Vec3::new(0., 4., 0.)).set_position(Position([1., 0.]));
+        solver
+            .apply_point_impulses_mut(&mut states, &[point_impulse], 0.)
+            .unwrap();
+        assert_abs_diff_eq!(states[0].qd, 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(states[1].qd, 2., epsilon = 1e-12);
+        assert_abs_diff_eq!(states[2].qd, 4., epsilon = 1e-12);
+        assert_eq!(states[0].q, 0.);
+
+        // Kicking a rack also spins up the pinion it's coupled to:
+        let pinion = RotationalFrame::new(PENDULUM1_ID.into())
+            .add_weight(Weight::new(2.).set_position(Position([0.5, 0.])))
+            .add_weight(Weight::new(2.).set_position(Position([-0.5, 0.])));
+        let rack = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(3.));
+        let coupling = Coupling::new(CART_ID.into(), PENDULUM1_ID.into()).set_ratio(-0.5);
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(rack))
+                .add_frame(Box::new(pinion))
+                .add_coupling(coupling),
+        );
+        let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 0. }];
+        solver.apply_impulses_mut(&mut states, &[0., 4.], 0.);
+        let rack_qd = 4. / (3. + 2. * 2. * 0.5 * 0.5 / (0.5 * 0.5));
+        assert_abs_diff_eq!(states[1].qd, rack_qd, epsilon = 1e-8);
+        assert_abs_diff_eq!(states[0].qd, rack_qd / -0.5, epsilon = 1e-8);
+
+        // A prescribed cart doesn't budge, while the pendulum hanging from it swings:
+        let cart = TrackFrame::new(CART_ID.into())
+            .add_weight(Weight::new(2.))
+            .add_child(Box::new(
+                RotationalFrame::new(PENDULUM1_ID.into())
+                    .add_weight(Weight::new(1.).set_position(Position([0., -2.]))),
+            ));
+        let prescribed_motion = PrescribedMotion::new(
+            CART_ID.into(),
+            MotionProfile::Polynomial {
+                coefficients: vec![0., 1.],
+            },
+        );
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(cart))
+                .add_prescribed_motion(prescribed_motion),
+        );
+        let mut states = vec![State { q: 0., qd: 1. }, State { q: 0., qd: 0. }];
+        solver.apply_impulses_mut(&mut states, &[5., 8.], 0.);
+        assert_eq!(states[0].qd, 1.);
+        assert_abs_diff_eq!(states[1].qd, 2., epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_get_batch_impulse_vel_deltas() {
+        // Solving several sets of impulses together matches solving each on its own, including
+        // ones that slacken the Atwood machine's rope:
+        let scene = get_atwood_machine_scene(6.);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 0. }];
+        let impulse_sets: Vec<&[f64]> = vec![&[0., -1.], &[0., 50.], &[2., 1.], &[-3., 0.]];
+        let batch_vel_deltas = super::get_batch_impulse_vel_deltas(
+            &frames,
+            &index_path_map,
+            &scene,
+            &states,
+            &impulse_sets,
+            0.,
+        );
+        assert_eq!(batch_vel_deltas.len(), impulse_sets.len());
+        for (impulses, vel_deltas) in impulse_sets.iter().zip(batch_vel_deltas) {
+            let expected_vel_deltas = super::get_impulse_vel_deltas(
+                &frames,
+                &index_path_map,
+                &scene,
+                &states,
+                impulses,
+                0.,
+            );
+            for (vel_delta, expected_vel_delta) in vel_deltas.iter().zip(expected_vel_deltas) {
+                assert_abs_diff_eq!(*vel_delta, expected_vel_delta, epsilon = 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_weight_inertia() {
+        // A physical pendulum, released from horizontal:
+        let pendulum = RotationalFrame::new(PENDULUM1_ID.into()).add_weight(
+            Weight::new(2.)
+                .set_position(Position([3., 0.]))
+                .set_inertia(1.5),
+        );
+        let scene = Scene::new().add_frame(Box::new(pendulum));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 0. }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0.], 0.);
+        assert_abs_diff_eq!(
+            qdds[0],
+            -2. * 10. * 3. / (2. * 3. * 3. + 1.5),
+            epsilon = 1e-8
+        );
+
+        // A flywheel on a cart: inertia couples into the rotation but not the translation, and
+        // a spinning flywheel exerts no force on the cart.
+        let flywheel =
+            RotationalFrame::new(PENDULUM1_ID.into()).add_weight(Weight::new(2.).set_inertia(1.5));
+        let cart = TrackFrame::new(CART_ID.into())
+            .add_weight(Weight::new(3.))
+            .add_child(Box::new(flywheel));
+        let scene = Scene::new().add_frame(Box::new(cart));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 0. }, State { q: 0.4, qd: 5. }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[5., 3.], 0.);
+        assert_abs_diff_eq!(qdds[0], 1., epsilon = 1e-8);
+        assert_abs_diff_eq!(qdds[1], 2., epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_shapes() {
+        // A uniform rod pendulum released from horizontal falls with `3g / 2L`, unlike a point
+        // mass at its tip or its midpoint:
+        let rod = Shape::Rod {
+            end_pos: Position([4., 0.]),
+            mass: 2.,
+            start_pos: Position([0., 0.]),
+        };
+        let pendulum = RotationalFrame::new(PENDULUM1_ID.into()).add_weight(rod.to_weight());
+        let scene = Scene::new().add_frame(Box::new(pendulum));
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let states = vec![State { q: 0., qd: 0. }];
+        let qdds = super::solve(&frames, &index_path_map, &scene, &states, &[0.], 0.);
+        assert_abs_diff_eq!(qdds[0], -3. * 10. / (2. * 4.), epsilon = 1e-8);
+    }
+
     #[test]
     fn test_new() {
         let solver = Solver::new(Scene::new());
         assert_eq!(solver.scene.frames.len(), 0);
     }
+
+    fn get_floor_scene(restitution: f64, friction: f64) -> Scene {
+        let ball = FreeFrame::new(BALL_ID.into()).add_weight(
+            Shape::Disc {
+                mass: 2.,
+                position: Position([0., 0.]),
+                radius: 0.5,
+            }
+            .to_weight(),
+        );
+        let floor = Obstacle::new(ObstacleShape::HalfPlane {
+            normal: Position([0., 1.]),
+            position: Position([0., 0.]),
+        })
+        .set_friction(friction)
+        .set_restitution(restitution);
+        Scene::new().add_frame(Box::new(ball)).add_obstacle(floor)
+    }
+
+    #[test]
+    fn test_obstacles() {
+        // A ball dropped onto the floor bounces back at half the speed it hit with:
+        let solver = Solver::new(get_floor_scene(0.5, 0.));
+        let mut states = vec![
+            State { q: 0., qd: 1. },
+            State { q: 2., qd: 0. },
+            State { q: 0., qd: 0. },
+        ];
+        let mut min_qd = 0_f64;
+        let mut max_qd = 0_f64;
+        for tick_index in 0..60 {
+            solver.tick_mut(
+                &mut states,
+                &[0., 0., 0.],
+                tick_index as f64 / 60.,
+                1. / 60.,
+            );
+            assert!(states[1].q >= 0.5 - 1e-8);
+            min_qd = min_qd.min(states[1].qd);
+            max_qd = max_qd.max(states[1].qd);
+        }
+        let impact_speed = (2. * 10. * 1.5_f64).sqrt();
+        assert_abs_diff_eq!(min_qd, -impact_speed, epsilon = 0.2);
+        assert_abs_diff_eq!(max_qd, 0.5 * impact_speed, epsilon = 0.2);
+        // Without friction, the bounce doesn't touch the horizontal velocity or the spin:
+        assert_abs_diff_eq!(states[0].qd, 1., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[2].qd, 0., epsilon = 1e-8);
+
+        // It eventually comes to rest on the floor:
+        for tick_index in 60..300 {
+            solver.tick_mut(
+                &mut states,
+                &[0., 0., 0.],
+                tick_index as f64 / 60.,
+                1. / 60.,
+            );
+        }
+        assert_abs_diff_eq!(states[1].q, 0.5, epsilon = 1e-8);
+        assert_abs_diff_eq!(states[1].qd, 0., epsilon = 1e-8);
+
+        // A pendulum swinging into a peg stops dead against it:
+        let pendulum = RotationalFrame::new(PENDULUM1_ID.into())
+            .add_weight(Weight::new(1.).set_position(Position([0., -2.])));
+        let peg = Obstacle::new(ObstacleShape::Circle {
+            center: Position([1., -1.]),
+            radius: 2_f64.sqrt() * 0.5,
+        });
+        let solver = Solver::new(Scene::new().add_frame(Box::new(pendulum)).add_obstacle(peg));
+        let mut states = vec![State { q: 0., qd: 3. }];
+        for tick_index in 0..60 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+            assert!(states[0].q <= PI / 4. + 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_obstacle_friction() {
+        // A disc sliding along the floor is slowed by friction until it rolls, which keeps its
+        // angular momentum about the contact point and leaves it at two thirds of its speed:
+        let solver = Solver::new(get_floor_scene(0., 0.5));
+        let mut states = vec![
+            State { q: 0., qd: 3. },
+            State { q: 0.5, qd: 0. },
+            State { q: 0., qd: 0. },
+        ];
+        for tick_index in 0..120 {
+            solver.tick_mut(
+                &mut states,
+                &[0., 0., 0.],
+                tick_index as f64 / 60.,
+                1. / 60.,
+            );
+        }
+        assert_abs_diff_eq!(states[0].qd, 2., epsilon = 1e-6);
+        assert_abs_diff_eq!(states[2].qd, -4., epsilon = 1e-6);
+        assert_abs_diff_eq!(states[1].q, 0.5, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_obstacle_shared_contacts() {
+        // A platform on a vertical track, sunk into the floor at both of its ends, is lifted out
+        // once rather than once for each end:
+        let platform = TrackFrame::new(CART_ID.into())
+            .set_angle(PI / 2.)
+            .add_weight(
+                Weight::new(1.)
+                    .set_position(Position([-1., 0.]))
+                    .set_radius(0.5),
+            )
+            .add_weight(
+                Weight::new(1.)
+                    .set_position(Position([1., 0.]))
+                    .set_radius(0.5),
+            );
+        let floor = Obstacle::new(ObstacleShape::HalfPlane {
+            normal: Position([0., 1.]),
+            position: Position([0., 0.]),
+        });
+        let scene = Scene::new()
+            .add_frame(Box::new(platform))
+            .add_obstacle(floor);
+        let frames = super::sort_frames(&scene.frames);
+        let index_path_map = super::get_index_path_map(&frames);
+        let mut states = vec![State { q: 0.4, qd: 0. }];
+        let contacts = super::get_contacts(&frames, &index_path_map, &scene, &states);
+        assert_eq!(contacts.len(), 2);
+        super::resolve_contacts_mut(&frames, &index_path_map, &scene, &mut states, &contacts, 0.);
+        assert_abs_diff_eq!(states[0].q, 0.5, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_get_overlapping_pairs() {
+        let circles = vec![
+            (Vec3::new(3., 0., 1.), 1.),
+            (Vec3::new(0., 0., 1.), 1.),
+            (Vec3::new(1.5, 0., 1.), 0.5),
+            (Vec3::new(1.5, 5., 1.), 0.5),
+        ];
+        assert_eq!(super::get_overlapping_pairs(&circles), vec![(1, 2), (2, 0)]);
+    }
+
+    fn get_cradle_scene(ball_count: usize, collisions: Option<Collisions>) -> Scene {
+        let scene = (0..ball_count).fold(Scene::new(), |scene, index| {
+            let pendulum = RotationalFrame::new(format!("ball{}", index).as_str().into())
+                .set_position(Position([index as f64 * 0.52, 0.]))
+                .add_weight(
+                    Weight::new(1.)
+                        .set_position(Position([0., -2.]))
+                        .set_radius(0.25),
+                );
+            scene.add_frame(Box::new(pendulum))
+        });
+        match collisions {
+            Some(collisions) => scene.set_collisions(collisions),
+            None => scene,
+        }
+    }
+
+    #[test]
+    fn test_collisions() {
+        // The frames come out in reverse, so the last state is for the leftmost pendulum, which
+        // starts swinging to the right.  Without collisions, it swings straight through the one
+        // next to it:
+        let solver = Solver::new(get_cradle_scene(2, None));
+        let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 1. }];
+        for tick_index in 0..6 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert!(states[1].qd > 0.9);
+        assert_abs_diff_eq!(states[0].qd, 0., epsilon = 1e-2);
+
+        // With elastic collisions, the two equal pendulums swap velocities:
+        let solver = Solver::new(get_cradle_scene(2, Some(Collisions::new(1.))));
+        let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 1. }];
+        for tick_index in 0..6 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[1].qd, 0., epsilon = 1e-2);
+        assert!(states[0].qd > 0.9);
+
+        // A Newton's cradle passes the swing along to the far ball:
+        let solver = Solver::new(get_cradle_scene(4, Some(Collisions::new(1.))));
+        let mut states = vec![
+            State { q: 0., qd: 0. },
+            State { q: 0., qd: 0. },
+            State { q: 0., qd: 0. },
+            State { q: 0., qd: 1. },
+        ];
+        for tick_index in 0..12 {
+            solver.tick_mut(&mut states, &[0.; 4], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert!(states[0].qd > 0.9);
+        for state in states[1..].iter() {
+            assert_abs_diff_eq!(state.qd, 0., epsilon = 2e-2);
+        }
+    }
+
+    #[test]
+    fn test_collisions_joints() {
+        // The links of a chain overlap where they're joined, but don't collide there, so the
+        // chain swings just as it would without collisions:
+        let get_chain_scene = |collisions: Option<Collisions>| {
+            let link = |id: &str| {
+                RotationalFrame::new(id.into())
+                    .add_weight(
+                        Weight::new(1.)
+                            .set_position(Position([0.1, 0.]))
+                            .set_radius(0.25),
+                    )
+                    .add_weight(
+                        Weight::new(1.)
+                            .set_position(Position([0.9, 0.]))
+                            .set_radius(0.25),
+                    )
+            };
+            let chain = link(PENDULUM1_ID).add_child(Box::new(
+                link(PENDULUM2_ID).set_position(Position([1., 0.])),
+            ));
+            let scene = Scene::new().add_frame(Box::new(chain));
+            match collisions {
+                Some(collisions) => scene.set_collisions(collisions),
+                None => scene,
+            }
+        };
+        let tick = |scene: Scene| {
+            let solver = Solver::new(scene);
+            let mut states = vec![State { q: 0., qd: 0. }, State { q: 0.5, qd: 0. }];
+            for tick_index in 0..30 {
+                solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+            }
+            states
+        };
+        let states = tick(get_chain_scene(Some(Collisions::new(0.))));
+        let expected_states = tick(get_chain_scene(None));
+        for (state, expected_state) in states.iter().zip(expected_states) {
+            assert_abs_diff_eq!(state.q, expected_state.q, epsilon = 1e-12);
+            assert_abs_diff_eq!(state.qd, expected_state.qd, epsilon = 1e-12);
+        }
+
+        // A pendulum bob hanging from a heavy cart still hits a post standing on the cart away
+        // from the joint, and bounces back off it:
+        let get_cart_scene = |collisions: Option<Collisions>| {
+            let pendulum = RotationalFrame::new(PENDULUM1_ID.into()).add_weight(
+                Weight::new(1.)
+                    .set_position(Position([0., -1.]))
+                    .set_radius(0.25),
+            );
+            let cart = TrackFrame::new(CART_ID.into())
+                .add_weight(Weight::new(100.).set_radius(0.25))
+                .add_weight(
+                    Weight::new(1.)
+                        .set_position(Position([1.2, -0.5]))
+                        .set_radius(0.25),
+                )
+                .add_child(Box::new(pendulum));
+            let scene = Scene::new().add_frame(Box::new(cart));
+            match collisions {
+                Some(collisions) => scene.set_collisions(collisions),
+                None => scene,
+            }
+        };
+        let tick = |scene: Scene| {
+            let solver = Solver::new(scene);
+            let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 4. }];
+            for tick_index in 0..15 {
+                solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+            }
+            states
+        };
+        assert!(tick(get_cart_scene(None))[1].qd > 0.);
+        assert!(tick(get_cart_scene(Some(Collisions::new(1.))))[1].qd < 0.);
+    }
+
+    #[test]
+    fn test_random_forces() {
+        // Thermal noise balances the resistance so that the cart's average kinetic energy settles
+        // at half the temperature:
+        let get_solver = |seed: u64| {
+            let cart = TrackFrame::new(CART_ID.into())
+                .set_resistance(4.)
+                .add_weight(Weight::new(2.));
+            Solver::new(
+                Scene::new()
+                    .add_frame(Box::new(cart))
+                    .add_random_force(RandomForce::new(CART_ID.into()).set_temperature(0.5))
+                    .set_seed(seed),
+            )
+        };
+        let solver = get_solver(1);
+        let mut states = vec![State { q: 0., qd: 0. }];
+        let mut kinetic_energy_sum = 0.;
+        let tick_count = 6000;
+        for tick_index in 0..tick_count {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+            kinetic_energy_sum += 0.5 * 2. * states[0].qd * states[0].qd;
+        }
+        assert_abs_diff_eq!(kinetic_energy_sum / tick_count as f64, 0.25, epsilon = 0.03);
+
+        // Runs are reproducible from the seed:
+        let run = |seed: u64| {
+            let solver = get_solver(seed);
+            let mut states = vec![State { q: 0., qd: 0. }];
+            for tick_index in 0..10 {
+                solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+            }
+            (states[0].q, states[0].qd)
+        };
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+    }
+
+    #[test]
+    fn test_mass_schedules() {
+        // A draining bucket pushed by a constant force speeds up as it gets lighter, reaching
+        // `force * ln(m0 / m)`:
+        let bucket = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(4.));
+        let mass_schedule = MassSchedule::new(
+            CART_ID.into(),
+            0,
+            MotionProfile::Polynomial {
+                coefficients: vec![4., -1.],
+            },
+        );
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(bucket))
+                .add_mass_schedule(mass_schedule),
+        );
+        let mut states = vec![State { q: 0., qd: 0. }];
+        for tick_index in 0..180 {
+            solver.tick_mut(&mut states, &[2.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].qd, 2. * 4_f64.ln(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_winch() {
+        // Reeling in a spinning weight to half its distance from the pivot (by prescribing the
+        // length of the line) keeps its angular momentum, so it spins four times as fast:
+        let winch = RotationalFrame::new(PENDULUM1_ID.into()).add_child(Box::new(
+            TrackFrame::new(CART_ID.into()).add_weight(Weight::new(1.)),
+        ));
+        let prescribed_motion = PrescribedMotion::new(
+            CART_ID.into(),
+            MotionProfile::Polynomial {
+                coefficients: vec![2., -0.5],
+            },
+        );
+        let solver = Solver::new(
+            Scene::new()
+                .set_gravity(Vec3::zeros())
+                .add_frame(Box::new(winch))
+                .add_prescribed_motion(prescribed_motion),
+        );
+        let mut states = vec![State { q: 0., qd: 1. }, State { q: 2., qd: -0.5 }];
+        for tick_index in 0..120 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[1].q, 1., epsilon = 1e-12);
+        assert_abs_diff_eq!(states[0].qd, 4., epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_damping() {
+        // Mass-proportional damping slows a coasting cart exponentially, regardless of its mass:
+        let cart = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(2.));
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(cart))
+                .add_damping(Damping::Rayleigh { alpha: 0.5 }),
+        );
+        let mut states = vec![State { q: 0., qd: 1. }];
+        for tick_index in 0..120 {
+            solver.tick_mut(&mut states, &[0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        assert_abs_diff_eq!(states[0].qd, (-1_f64).exp(), epsilon = 1e-8);
+
+        // A dashpot between two carts evens out their velocities while keeping their total
+        // momentum:
+        let cart1 = TrackFrame::new(CART_ID.into()).add_weight(Weight::new(1.));
+        let cart2 = TrackFrame::new(BALL_ID.into()).add_weight(Weight::new(1.));
+        let dashpot = Damping::Matrix {
+            frame_ids: vec![CART_ID.into(), BALL_ID.into()],
+            matrix: nalgebra::DMatrix::from_row_slice(2, 2, &[1., -1., -1., 1.]),
+        };
+        let solver = Solver::new(
+            Scene::new()
+                .add_frame(Box::new(cart1))
+                .add_frame(Box::new(cart2))
+                .add_damping(dashpot),
+        );
+        let mut states = vec![State { q: 0., qd: 0. }, State { q: 0., qd: 2. }];
+        for tick_index in 0..60 {
+            solver.tick_mut(&mut states, &[0., 0.], tick_index as f64 / 60., 1. / 60.);
+        }
+        let rel_vel = 2. * (-2_f64).exp();
+        assert_abs_diff_eq!(states[1].qd, 1. + rel_vel / 2., epsilon = 1e-8);
+        assert_abs_diff_eq!(states[0].qd, 1. - rel_vel / 2., epsilon = 1e-8);
+    }
 }
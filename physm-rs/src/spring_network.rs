@@ -0,0 +1,349 @@
+use crate::json;
+use crate::Error;
+use crate::FrameId;
+use crate::Position;
+use crate::Weight;
+
+/// Ties one of a network's particles (by index) to one of a frame's weights (by index) with a
+/// spring of zero rest length, so the particle should start out at the weight's position.
+#[derive(Debug, PartialEq)]
+pub struct Attachment {
+    pub frame_id: FrameId,
+    pub particle_index: usize,
+    pub weight_index: usize,
+}
+
+impl Attachment {
+    pub fn new(frame_id: FrameId, weight_index: usize, particle_index: usize) -> Self {
+        Self {
+            frame_id,
+            particle_index,
+            weight_index,
+        }
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Attachment {
+            frame_id: json::map_value_item(value, "frame", json::value_to_str)?.into(),
+            particle_index: json::map_value_item(value, "particle", json::value_to_u64)? as usize,
+            weight_index: json::map_obj_item_or_default(obj, "weight", json::value_to_u64)?
+                as usize,
+        })
+    }
+}
+
+/// Soft body or cloth made of free particles joined by springs, e.g. a flag or a rubber bumper.
+/// Each particle is a weight at its rest position in world space, which moves in x and y with
+/// no rotation, and feels gravity, drag, wind and fields like the frames' weights do.  Springs
+/// join pairs of particles (by index) with rest lengths taken from the particles' rest positions,
+/// and all of them share the network's `stiffness` and `damping`, as do its attachments to the
+/// frames' weights.
+///
+/// The particles' states come after the frames' coordinates, as displacements `[x, y]` from
+/// their rest positions, so zeroed states start the network at rest.  Stiff springs call for
+/// short ticks, since the particles are integrated along with the frames.
+#[derive(Debug, PartialEq)]
+pub struct SpringNetwork {
+    pub attachments: Vec<Attachment>,
+    pub damping: f64,
+    pub particles: Vec<Weight>,
+    /// Rest length of each of the springs, worked out as they're added so that the solver doesn't
+    /// have to for every evaluation of the forces.
+    pub rest_lengths: Vec<f64>,
+    pub springs: Vec<(usize, usize)>,
+    pub stiffness: f64,
+}
+
+impl SpringNetwork {
+    pub fn new(stiffness: f64) -> Self {
+        Self {
+            attachments: Vec::new(),
+            damping: 0.,
+            particles: Vec::new(),
+            rest_lengths: Vec::new(),
+            springs: Vec::new(),
+            stiffness,
+        }
+    }
+
+    /// Returns a rectangular sheet of particles, `columns` wide and `rows` tall, hanging down
+    /// and to the right from `position` with the given `spacing`.  Particle `column + row *
+    /// columns` is joined to its horizontal, vertical and diagonal neighbours, where the
+    /// diagonal springs keep the sheet from shearing.
+    pub fn new_grid(
+        stiffness: f64,
+        position: Position,
+        columns: usize,
+        rows: usize,
+        spacing: f64,
+        get_particle: impl Fn() -> Weight,
+    ) -> Self {
+        let get_index = |column: usize, row: usize| column + row * columns;
+        let mut network = SpringNetwork::new(stiffness);
+        for row in 0..rows {
+            for column in 0..columns {
+                network = network.add_particle(get_particle().set_position(Position([
+                    position.0[0] + column as f64 * spacing,
+                    position.0[1] - row as f64 * spacing,
+                ])));
+                if column > 0 {
+                    network =
+                        network.add_spring(get_index(column - 1, row), get_index(column, row));
+                }
+                if row > 0 {
+                    network =
+                        network.add_spring(get_index(column, row - 1), get_index(column, row));
+                }
+                if column > 0 && row > 0 {
+                    network = network
+                        .add_spring(get_index(column - 1, row - 1), get_index(column, row))
+                        .add_spring(get_index(column, row - 1), get_index(column - 1, row));
+                }
+            }
+        }
+        network
+    }
+
+    pub fn add_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    pub fn set_damping(mut self, damping: f64) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    pub fn add_particle(mut self, particle: Weight) -> Self {
+        self.particles.push(particle);
+        self
+    }
+
+    /// Joins two particles that have already been added with a spring, which is at rest at the
+    /// particles' current distance apart.
+    pub fn add_spring(mut self, index1: usize, index2: usize) -> Self {
+        let offset =
+            self.particles[index2].position.to_vec3() - self.particles[index1].position.to_vec3();
+        self.rest_lengths.push(offset.norm());
+        self.springs.push((index1, index2));
+        self
+    }
+
+    /// Accepts either a `Grid` with particles of the given `mass`, `drag` and `radius`, or a
+    /// `Graph` with explicit `particles` (as weights) and `springs` (as pairs of indices).
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        let type_name = json::map_value_item(value, "type", json::value_to_str)?;
+        let stiffness = json::map_value_item(value, "stiffness", json::value_to_f64)?;
+        let network = match type_name {
+            "Graph" => {
+                let particles = json::map_value_item(value, "particles", |value| {
+                    json::value_to_vec(value, Weight::from_json_value)
+                })?;
+                let springs = json::map_value_item(value, "springs", |value| {
+                    json::value_to_vec(value, value_to_spring)
+                })?;
+                check_particle_indices(
+                    springs
+                        .iter()
+                        .flat_map(|(index1, index2)| vec![*index1, *index2]),
+                    particles.len(),
+                )?;
+                springs.into_iter().fold(
+                    SpringNetwork {
+                        particles,
+                        ..SpringNetwork::new(stiffness)
+                    },
+                    |network, (index1, index2)| network.add_spring(index1, index2),
+                )
+            }
+            "Grid" => {
+                let mass = json::map_value_item(value, "mass", json::value_to_f64)?;
+                let drag = json::map_obj_item_or_default(obj, "drag", json::value_to_f64)?;
+                let radius = json::map_obj_item_or_default(obj, "radius", json::value_to_f64)?;
+                SpringNetwork::new_grid(
+                    stiffness,
+                    json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
+                    json::map_value_item(value, "columns", json::value_to_u64)? as usize,
+                    json::map_value_item(value, "rows", json::value_to_u64)? as usize,
+                    json::map_value_item(value, "spacing", json::value_to_f64)?,
+                    || Weight::new(mass).set_drag(drag).set_radius(radius),
+                )
+            }
+            _ => return Err(Error(format!("Invalid spring network type: {}", type_name))),
+        };
+        let network = SpringNetwork {
+            attachments: json::map_obj_item_or_default(obj, "attachments", |value| {
+                json::value_to_vec(value, Attachment::from_json_value)
+            })?,
+            damping: json::map_obj_item_or_default(obj, "damping", json::value_to_f64)?,
+            ..network
+        };
+        check_particle_indices(
+            network
+                .attachments
+                .iter()
+                .map(|attachment| attachment.particle_index),
+            network.particles.len(),
+        )?;
+        Ok(network)
+    }
+}
+
+fn check_particle_indices(
+    mut indices: impl Iterator<Item = usize>,
+    particle_count: usize,
+) -> Result<(), Error> {
+    match indices.find(|index| *index >= particle_count) {
+        Some(index) => Err(Error(format!("Invalid particle index: {}", index))),
+        None => Ok(()),
+    }
+}
+
+fn value_to_spring(value: &serde_json::Value) -> Result<(usize, usize), Error> {
+    match value.as_array().map(|spring| spring.as_slice()) {
+        Some([index1, index2]) => Ok((
+            json::value_to_u64(index1)? as usize,
+            json::value_to_u64(index2)? as usize,
+        )),
+        _ => Err(Error(format!(
+            "Expected spring to be a pair of particle indices; got {}",
+            value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_grid() {
+        let network =
+            SpringNetwork::new_grid(10., Position([1., 2.]), 3, 2, 0.5, || Weight::new(0.1));
+        assert_eq!(
+            network
+                .particles
+                .iter()
+                .map(|particle| &particle.position)
+                .collect::<Vec<_>>(),
+            vec![
+                &Position([1., 2.]),
+                &Position([1.5, 2.]),
+                &Position([2., 2.]),
+                &Position([1., 1.5]),
+                &Position([1.5, 1.5]),
+                &Position([2., 1.5]),
+            ]
+        );
+        assert_eq!(
+            network.springs,
+            vec![
+                (0, 1),
+                (1, 2),
+                (0, 3),
+                (3, 4),
+                (1, 4),
+                (0, 4),
+                (1, 3),
+                (4, 5),
+                (2, 5),
+                (1, 5),
+                (2, 4),
+            ]
+        );
+        let diagonal = 0.5 * 2_f64.sqrt();
+        assert_eq!(
+            network.rest_lengths,
+            vec![0.5, 0.5, 0.5, 0.5, 0.5, diagonal, diagonal, 0.5, 0.5, diagonal, diagonal]
+        );
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "attachments": [{"frame": "a", "particle": 0}],
+              "damping": 0.5,
+              "particles": [
+                {"drag": 0, "mass": 1, "position": [0, 0]},
+                {"drag": 0, "mass": 2, "position": [1, 0], "radius": 0.1}
+              ],
+              "springs": [[0, 1]],
+              "stiffness": 100,
+              "type": "Graph"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            SpringNetwork::from_json_value(&json_value).unwrap(),
+            SpringNetwork::new(100.)
+                .set_damping(0.5)
+                .add_particle(Weight::new(1.))
+                .add_particle(
+                    Weight::new(2.)
+                        .set_position(Position([1., 0.]))
+                        .set_radius(0.1)
+                )
+                .add_spring(0, 1)
+                .add_attachment(Attachment::new("a".into(), 0, 0))
+        );
+
+        let json = r#"
+            {
+              "attachments": [{"frame": "a", "particle": 1, "weight": 2}],
+              "columns": 2,
+              "drag": 0.1,
+              "mass": 0.5,
+              "position": [1, 2],
+              "rows": 1,
+              "spacing": 0.25,
+              "stiffness": 100,
+              "type": "Grid"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            SpringNetwork::from_json_value(&json_value).unwrap(),
+            SpringNetwork::new_grid(100., Position([1., 2.]), 2, 1, 0.25, || {
+                Weight::new(0.5).set_drag(0.1)
+            })
+            .add_attachment(Attachment::new("a".into(), 2, 1))
+        );
+    }
+
+    #[test]
+    fn test_from_json_value_errors() {
+        let json = r#"{"particles": [], "springs": [[0, 1, 2]], "stiffness": 1, "type": "Graph"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            SpringNetwork::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Expected spring to be a pair of particle indices; got [0,1,2]"
+        );
+
+        let json = r#"
+            {
+              "particles": [{"drag": 0, "mass": 1, "position": [0, 0]}],
+              "springs": [[0, 1]],
+              "stiffness": 1,
+              "type": "Graph"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            SpringNetwork::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Invalid particle index: 1"
+        );
+
+        let json = r#"{"stiffness": 1, "type": "Mesh"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            SpringNetwork::from_json_value(&json_value)
+                .unwrap_err()
+                .to_string(),
+            "Invalid spring network type: Mesh"
+        );
+    }
+}
@@ -7,6 +7,9 @@ use crate::Mat3;
 use crate::Position;
 use crate::Weight;
 
+/// Frame that slides along a straight track in the direction of `angle`.  Like every frame, it's
+/// positioned in its parent's coordinate system, so under a `RotationalFrame` the track turns with
+/// the parent (e.g. a bead on a spinning rod or a telescoping pendulum).
 #[derive(Debug)]
 pub struct TrackFrame {
     pub angle: f64,
@@ -22,7 +25,7 @@ impl TrackFrame {
         TrackFrame {
             angle: 0.,
             children: Vec::new(),
-            id: id,
+            id,
             position: Position([0., 0.]),
             resistance: 0.,
             weights: Vec::new(),
@@ -58,11 +61,13 @@ impl TrackFrame {
         let obj = json::value_to_json_obj(value)?;
         Ok(TrackFrame {
             angle: json::map_obj_item_or_default(obj, "angle", json::value_to_f64)?,
-            children: json::map_obj_item_or_default(obj, "frames", json::value_to_frames)?,
-            id: json::map_value_item(value, &"id", json::value_to_str)?.into(),
+            children: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            id: json::map_value_item(value, "id", json::value_to_str)?.into(),
             position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
             resistance: json::map_obj_item_or_default(obj, "resistance", json::value_to_f64)?,
-            weights: json::map_obj_item_or_default(obj, "weights", json::value_to_weights)?,
+            weights: json::obj_to_frame_weights(obj)?,
         })
     }
 }
@@ -84,7 +89,8 @@ impl Frame for TrackFrame {
         &self.weights
     }
 
-    fn get_local_pos_matrix(&self, q: f64) -> Mat3 {
+    fn get_local_pos_matrix(&self, qs: &[f64]) -> Mat3 {
+        let q = qs[0];
         Mat3::new(
             1.,
             0.,
@@ -98,7 +104,7 @@ impl Frame for TrackFrame {
         )
     }
 
-    fn get_local_vel_matrix(&self, _q: f64) -> Mat3 {
+    fn get_local_vel_matrix(&self, _qs: &[f64], _index: usize) -> Mat3 {
         Mat3::new(
             0.,
             0.,
@@ -193,7 +199,7 @@ mod tests {
                 }
               ]
             }"#;
-        let json_value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
         let frame = TrackFrame::from_json_value(&json_value).unwrap();
         assert_eq!(frame.angle, 3.5);
         assert_eq!(frame.id, "a");
@@ -222,7 +228,7 @@ mod tests {
         let frame = TrackFrame::new("a".to_owned());
         let frame = frame.set_position(Position([3., 4.]));
         assert_abs_diff_eq!(
-            frame.get_local_pos_matrix(7.),
+            frame.get_local_pos_matrix(&[7.]),
             Mat3::new(
                 1.000, 0.000, 10.00, //
                 0.000, 1.000, 4.000, //
@@ -232,7 +238,7 @@ mod tests {
         );
         let frame = frame.set_angle(PI / 3.);
         assert_abs_diff_eq!(
-            frame.get_local_pos_matrix(7.),
+            frame.get_local_pos_matrix(&[7.]),
             Mat3::new(
                 1.000, 0.000, 6.500, //
                 0.000, 1.000, 10.06, //
@@ -246,7 +252,7 @@ mod tests {
     fn test_get_local_vel_matrix() {
         let frame = TrackFrame::new("a".to_owned()).set_position(Position([3., 4.]));
         assert_abs_diff_eq!(
-            frame.get_local_vel_matrix(7.),
+            frame.get_local_vel_matrix(&[7.], 0),
             Mat3::new(
                 0.000, 0.000, 1.000, //
                 0.000, 0.000, 0.000, //
@@ -256,7 +262,7 @@ mod tests {
         );
         let frame = frame.set_angle(PI / 3.);
         assert_abs_diff_eq!(
-            frame.get_local_vel_matrix(7.),
+            frame.get_local_vel_matrix(&[7.], 0),
             Mat3::new(
                 0.000, 0.000, 0.500, //
                 0.000, 0.000, 0.866, //
@@ -271,6 +277,9 @@ mod tests {
         let frame = TrackFrame::new("a".to_owned())
             .set_position(Position([3., 4.]))
             .set_angle(PI / 3.);
-        assert_abs_diff_eq!(frame.get_local_accel_matrix(PI / 3.), Mat3::zeros());
+        assert_abs_diff_eq!(
+            frame.get_local_accel_matrix(&[PI / 3.], 0, 0),
+            Mat3::zeros()
+        );
     }
 }
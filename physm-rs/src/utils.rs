@@ -1,3 +1,18 @@
+/// Linearly interpolates between `(x, y)` points in order of increasing `x`, where the first
+/// point's `y` applies at smaller `x`.  Returns `None` from the last point's `x` onwards, which
+/// tables handle in their own ways.
+pub fn interpolate(points: &[(f64, f64)], x: f64) -> Option<f64> {
+    let index = points.iter().position(|(point_x, _)| *point_x > x)?;
+    Some(match index {
+        0 => points[0].1,
+        _ => {
+            let (x1, y1) = points[index - 1];
+            let (x2, y2) = points[index];
+            y1 + (y2 - y1) * (x - x1) / (x2 - x1)
+        }
+    })
+}
+
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
     // `set_panic_hook` function at least once during initialization, and then
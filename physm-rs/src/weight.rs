@@ -1,3 +1,4 @@
+use crate::json;
 use crate::Error;
 use crate::Position;
 
@@ -6,6 +7,14 @@ pub struct Weight {
     pub mass: f64,
     pub position: Position,
     pub drag: f64,
+    pub inertia: f64,
+    pub quadratic_drag: f64,
+    /// Collision radius, which contacts treat the weight as a disc of (or as a point, if zero).
+    pub radius: f64,
+    /// Volume of fluid that the weight displaces when fully submerged, for buoyancy.
+    pub volume: f64,
+    /// Labels that interactions can select weights by, e.g. `"magnet"`.
+    pub tags: Vec<String>,
 }
 
 impl Weight {
@@ -14,6 +23,11 @@ impl Weight {
             mass,
             position: Position([0., 0.]),
             drag: 0.,
+            inertia: 0.,
+            quadratic_drag: 0.,
+            radius: 0.,
+            volume: 0.,
+            tags: Vec::new(),
         }
     }
 
@@ -32,6 +46,31 @@ impl Weight {
         self
     }
 
+    pub fn set_quadratic_drag(mut self, quadratic_drag: f64) -> Self {
+        self.quadratic_drag = quadratic_drag;
+        self
+    }
+
+    pub fn set_inertia(mut self, inertia: f64) -> Self {
+        self.inertia = inertia;
+        self
+    }
+
+    pub fn set_radius(mut self, radius: f64) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn set_volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    pub fn add_tag(mut self, tag: String) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
     pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
         let obj = match value {
             serde_json::Value::Object(obj) => Ok(obj),
@@ -41,6 +80,17 @@ impl Weight {
             mass: obj["mass"].as_f64().unwrap_or(1.),
             position: Position::from_json_value(&obj["position"])?,
             drag: obj["drag"].as_f64().unwrap_or(0.),
+            inertia: json::map_obj_item_or_default(obj, "inertia", json::value_to_f64)?,
+            quadratic_drag: json::map_obj_item_or_default(
+                obj,
+                "quadraticDrag",
+                json::value_to_f64,
+            )?,
+            radius: json::map_obj_item_or_default(obj, "radius", json::value_to_f64)?,
+            volume: json::map_obj_item_or_default(obj, "volume", json::value_to_f64)?,
+            tags: json::map_obj_item_or_default(obj, "tags", |value| {
+                json::value_to_vec(value, json::value_to_string)
+            })?,
         })
     }
 }
@@ -58,6 +108,17 @@ mod tests {
         let weight = weight.set_position(Position([3., 4.]));
         assert_eq!(weight.mass, 7.);
         assert_eq!(weight.position, Position([3., 4.]));
+        assert_eq!(weight.inertia, 0.);
+
+        let weight = weight
+            .set_inertia(2.5)
+            .set_quadratic_drag(0.1)
+            .set_radius(0.5)
+            .set_volume(0.2);
+        assert_eq!(weight.inertia, 2.5);
+        assert_eq!(weight.quadratic_drag, 0.1);
+        assert_eq!(weight.radius, 0.5);
+        assert_eq!(weight.volume, 0.2);
     }
 
     #[test]
@@ -66,13 +127,23 @@ mod tests {
             {
                 "mass": 34,
                 "position": [56, 78.9],
-                "drag": 12
+                "drag": 12,
+                "inertia": 3.5,
+                "quadraticDrag": 0.25,
+                "radius": 1.5,
+                "volume": 0.25,
+                "tags": ["magnet"]
             }"#;
-        let json_value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
         let weight = Weight::from_json_value(&json_value).unwrap();
         assert_eq!(weight.mass, 34.);
         assert_eq!(weight.position, Position([56., 78.9]));
         assert_eq!(weight.drag, 12.);
+        assert_eq!(weight.inertia, 3.5);
+        assert_eq!(weight.quadratic_drag, 0.25);
+        assert_eq!(weight.radius, 1.5);
+        assert_eq!(weight.volume, 0.25);
+        assert_eq!(weight.tags, vec!["magnet"]);
     }
 
     #[test]
@@ -83,7 +154,7 @@ mod tests {
                 "position": [56, 78.9],
                 "drag": 12
             }]"#;
-        let json_value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
         assert_eq!(
             Weight::from_json_value(&json_value)
                 .err()
@@ -0,0 +1,212 @@
+use crate::json;
+use crate::Error;
+use crate::Frame;
+use crate::FrameBox;
+use crate::FrameId;
+use crate::Mat3;
+use crate::Position;
+use crate::Weight;
+
+/// Wheel that rolls without slipping along a straight ground line in the direction of `angle`.
+/// Its coordinate is the wheel's counter-clockwise rotation about its center, which starts at
+/// `position`; rolling ties the two together, so the center moves `radius * q` backwards along the
+/// ground (i.e. forwards for clockwise rotation).
+///
+/// Weights rotate with the wheel, so a `Shape::Disc` (or a weight with `inertia`) at the center
+/// gives the wheel both translational and rotational kinetic energy.  Child frames also rotate with
+/// the wheel; e.g. a `RotationalFrame` body on the axle measures its angle relative to the wheel's.
+#[derive(Debug)]
+pub struct WheelFrame {
+    pub angle: f64,
+    pub children: Vec<FrameBox>,
+    pub id: FrameId,
+    pub position: Position,
+    pub radius: f64,
+    pub resistance: f64,
+    pub weights: Vec<Weight>,
+}
+
+impl WheelFrame {
+    pub fn new(id: FrameId, radius: f64) -> Self {
+        Self {
+            angle: 0.,
+            children: Vec::new(),
+            id,
+            position: Position([0., 0.]),
+            radius,
+            resistance: 0.,
+            weights: Vec::new(),
+        }
+    }
+
+    pub fn set_angle(mut self, angle: f64) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    pub fn add_child(mut self, child: FrameBox) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    pub fn set_position(mut self, position: Position) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn set_resistance(mut self, resistance: f64) -> Self {
+        self.resistance = resistance;
+        self
+    }
+
+    pub fn add_weight(mut self, weight: Weight) -> Self {
+        self.weights.push(weight);
+        self
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(WheelFrame {
+            angle: json::map_obj_item_or_default(obj, "angle", json::value_to_f64)?,
+            children: json::map_obj_item_or_default(obj, "frames", |value| {
+                json::value_to_vec(value, json::value_to_frame)
+            })?,
+            id: json::map_value_item(value, "id", json::value_to_str)?.into(),
+            position: json::map_obj_item_or_default(obj, "position", Position::from_json_value)?,
+            radius: json::map_value_item(value, "radius", json::value_to_f64)?,
+            resistance: json::map_obj_item_or_default(obj, "resistance", json::value_to_f64)?,
+            weights: json::obj_to_frame_weights(obj)?,
+        })
+    }
+}
+
+impl Frame for WheelFrame {
+    fn get_children(&self) -> &[FrameBox] {
+        &self.children
+    }
+
+    fn get_id(&self) -> &FrameId {
+        &self.id
+    }
+
+    fn get_resistance(&self) -> f64 {
+        self.resistance
+    }
+
+    fn get_weights(&self) -> &[Weight] {
+        &self.weights
+    }
+
+    fn get_local_pos_matrix(&self, qs: &[f64]) -> Mat3 {
+        let q = qs[0];
+        let distance = -self.radius * q;
+        Mat3::new(
+            q.cos(),
+            -q.sin(),
+            self.position.0[0] + distance * self.angle.cos(),
+            q.sin(),
+            q.cos(),
+            self.position.0[1] + distance * self.angle.sin(),
+            0.,
+            0.,
+            1.,
+        )
+    }
+
+    fn get_local_vel_matrix(&self, qs: &[f64], _index: usize) -> Mat3 {
+        let q = qs[0];
+        Mat3::new(
+            -q.sin(),
+            -q.cos(),
+            -self.radius * self.angle.cos(),
+            q.cos(),
+            -q.sin(),
+            -self.radius * self.angle.sin(),
+            0.,
+            0.,
+            0.,
+        )
+    }
+
+    fn get_local_accel_matrix(&self, qs: &[f64], _index1: usize, _index2: usize) -> Mat3 {
+        let q = qs[0];
+        Mat3::new(-q.cos(), q.sin(), 0., -q.sin(), -q.cos(), 0., 0., 0., 0.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "angle": -0.1,
+              "id": "a",
+              "position": [
+                1,
+                2
+              ],
+              "radius": 0.5,
+              "shapes": [
+                {
+                  "mass": 2,
+                  "radius": 0.5,
+                  "type": "Disc"
+                }
+              ],
+              "type": "WheelFrame"
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let frame = WheelFrame::from_json_value(&json_value).unwrap();
+        assert_eq!(frame.angle, -0.1);
+        assert_eq!(frame.id, "a");
+        assert_eq!(frame.position, Position([1., 2.]));
+        assert_eq!(frame.radius, 0.5);
+        assert_eq!(
+            frame.weights,
+            vec![Weight::new(2.).set_inertia(0.25).set_radius(0.5)]
+        );
+
+        let json = r#"{"id": "a", "type": "WheelFrame"}"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(WheelFrame::from_json_value(&json_value).is_err());
+    }
+
+    #[test]
+    fn test_get_local_matrices() {
+        let frame = WheelFrame::new("a".into(), 2.).set_position(Position([1., 2.]));
+        // A quarter turn counter-clockwise rolls the center back by a quarter of the perimeter:
+        let x = 1. - PI;
+        assert_abs_diff_eq!(
+            frame.get_local_pos_matrix(&[PI / 2.]),
+            Mat3::new(
+                0., -1., x, //
+                1., 0., 2., //
+                0., 0., 1., //
+            ),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_vel_matrix(&[PI / 2.], 0),
+            Mat3::new(
+                -1., 0., -2., //
+                0., -1., 0., //
+                0., 0., 0., //
+            ),
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            frame.get_local_accel_matrix(&[PI / 2.], 0, 0),
+            Mat3::new(
+                0., 1., 0., //
+                -1., 0., 0., //
+                0., 0., 0., //
+            ),
+            epsilon = 1e-12
+        );
+    }
+}
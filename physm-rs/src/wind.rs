@@ -0,0 +1,111 @@
+use std::f64::consts::PI;
+
+use crate::json;
+use crate::Error;
+use crate::Vec3;
+
+/// Air velocity field that weights' drag is computed against.  The wind blows at a base velocity,
+/// plus a shear term that grows linearly with height, plus an optional periodic "1-cosine" gust
+/// that ramps up to the full gust velocity and back down once per gust period.
+#[derive(Debug, PartialEq)]
+pub struct Wind {
+    pub gust_period: f64,
+    pub gust_velocity: Vec3,
+    pub shear: Vec3,
+    pub velocity: Vec3,
+}
+
+impl Wind {
+    pub fn new(velocity: Vec3) -> Self {
+        Self {
+            gust_period: 0.,
+            gust_velocity: Vec3::zeros(),
+            shear: Vec3::zeros(),
+            velocity,
+        }
+    }
+
+    pub fn set_gust(mut self, gust_velocity: Vec3, gust_period: f64) -> Self {
+        self.gust_velocity = gust_velocity;
+        self.gust_period = gust_period;
+        self
+    }
+
+    pub fn set_shear(mut self, shear: Vec3) -> Self {
+        self.shear = shear;
+        self
+    }
+
+    pub fn get_velocity(&self, pos: &Vec3, time: f64) -> Vec3 {
+        let gust_factor = match self.gust_period > 0. {
+            true => (1. - (2. * PI * time / self.gust_period).cos()) / 2.,
+            false => 0.,
+        };
+        self.velocity + self.shear * pos[1] + self.gust_velocity * gust_factor
+    }
+
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self, Error> {
+        let obj = json::value_to_json_obj(value)?;
+        Ok(Wind {
+            gust_period: json::map_obj_item_or_default(obj, "gustPeriod", json::value_to_f64)?,
+            gust_velocity: json::map_obj_item_or_default(obj, "gustVelocity", json::value_to_vec3)?,
+            shear: json::map_obj_item_or_default(obj, "shear", json::value_to_vec3)?,
+            velocity: json::map_obj_item_or_default(obj, "velocity", json::value_to_vec3)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let wind = Wind::new(Vec3::new(3., 0., 0.));
+        assert_eq!(wind.velocity, Vec3::new(3., 0., 0.));
+        assert_eq!(wind.gust_velocity, Vec3::zeros());
+        assert_eq!(wind.gust_period, 0.);
+        assert_eq!(wind.shear, Vec3::zeros());
+
+        let wind = wind
+            .set_gust(Vec3::new(2., 1., 0.), 4.)
+            .set_shear(Vec3::new(0.5, 0., 0.));
+        assert_eq!(wind.gust_velocity, Vec3::new(2., 1., 0.));
+        assert_eq!(wind.gust_period, 4.);
+        assert_eq!(wind.shear, Vec3::new(0.5, 0., 0.));
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let json = r#"
+            {
+              "gustPeriod": 4,
+              "gustVelocity": [2, 1],
+              "shear": [0.5, 0],
+              "velocity": [3, 0]
+            }"#;
+        let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            Wind::from_json_value(&json_value).unwrap(),
+            Wind::new(Vec3::new(3., 0., 0.))
+                .set_gust(Vec3::new(2., 1., 0.), 4.)
+                .set_shear(Vec3::new(0.5, 0., 0.))
+        );
+    }
+
+    #[test]
+    fn test_get_velocity() {
+        let wind = Wind::new(Vec3::new(3., 0., 0.));
+        let pos = Vec3::new(7., 2., 1.);
+        assert_eq!(wind.get_velocity(&pos, 1.5), Vec3::new(3., 0., 0.));
+
+        let wind = wind.set_shear(Vec3::new(0.5, 0., 0.));
+        assert_eq!(wind.get_velocity(&pos, 1.5), Vec3::new(4., 0., 0.));
+
+        let wind = wind.set_gust(Vec3::new(2., 1., 0.), 4.);
+        assert_abs_diff_eq!(wind.get_velocity(&pos, 0.), Vec3::new(4., 0., 0.));
+        assert_abs_diff_eq!(wind.get_velocity(&pos, 1.), Vec3::new(5., 0.5, 0.));
+        assert_abs_diff_eq!(wind.get_velocity(&pos, 2.), Vec3::new(6., 1., 0.));
+        assert_abs_diff_eq!(wind.get_velocity(&pos, 4.), Vec3::new(4., 0., 0.));
+    }
+}